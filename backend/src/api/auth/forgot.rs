@@ -0,0 +1,70 @@
+use actix_web::{post, web, HttpRequest, Responder};
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+
+use crate::api::respond;
+use crate::mailer::{self, Email};
+use crate::models::{email_collation, normalize_email, ForgotPasswordIn, PasswordResetDb};
+use crate::server::AppState;
+use crate::sessions;
+
+/// How long a reset link stays valid.
+const RESET_TOKEN_MINUTES: i64 = 60;
+
+/// Mail a password reset link.
+///
+/// Always answers `{ ok: true }`, so the endpoint can't be used to find out
+/// which emails have an account. A new request replaces any earlier link.
+#[post("/api/auth/forgot")]
+pub async fn auth_forgot(
+    data: web::Data<AppState>,
+    _req: HttpRequest,
+    body: web::Json<ForgotPasswordIn>,
+) -> impl Responder {
+    let email = normalize_email(&body.email);
+    if email.is_empty() || !email.contains('@') {
+        return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid email");
+    }
+
+    let user = match data
+        .users
+        .find_one(doc! { "email": &email })
+        .collation(email_collation())
+        .await
+    {
+        Ok(Some(u)) => u,
+        Ok(None) => return respond::ok_json(serde_json::json!({ "ok": true })),
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    // only the latest link works
+    if data.password_resets.delete_many(doc! { "userId": user.id }).await.is_err() {
+        return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+    }
+
+    let token = sessions::new_token();
+    let reset = PasswordResetDb {
+        id: ObjectId::new(),
+        user_id: user.id,
+        token_hash: sessions::hash_token(&token),
+        expires_at: DateTime::from_millis(DateTime::now().timestamp_millis() + RESET_TOKEN_MINUTES * 60 * 1000),
+    };
+    if data.password_resets.insert_one(&reset).await.is_err() {
+        return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+    }
+
+    mailer::send_later(
+        data.mailer.clone(),
+        Email {
+            to: user.email,
+            subject: "Reset your password".to_string(),
+            body: format!(
+                "Hi {},\n\nOpen this link to choose a new password:\n{}/reset-password?token={token}\n\n\
+                 The link works once and expires in {RESET_TOKEN_MINUTES} minutes. \
+                 If you didn't ask for it, you can ignore this email.\n",
+                user.name, data.app_url,
+            ),
+        },
+    );
+
+    respond::ok_json(serde_json::json!({ "ok": true }))
+}
@@ -1,15 +1,15 @@
 use actix_web::{post, web, HttpRequest, Responder};
 use mongodb::bson::doc;
 
+use crate::api::auth::tokens::sign_in;
 use crate::api::respond;
-use crate::auth;
-use crate::models::{AuthOut, LoginIn, UserDb, UserOut};
+use crate::models::{email_collation, normalize_email, LoginIn};
 use crate::server::AppState;
 
 #[post("/api/auth/login")]
 pub async fn auth_login(
     data: web::Data<AppState>,
-    _req: HttpRequest,
+    req: HttpRequest,
     body: web::Json<LoginIn>,
 ) -> impl Responder {
     let body = body.into_inner();
@@ -17,7 +17,12 @@ pub async fn auth_login(
         return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Email and password are required");
     }
 
-    let user = match data.users.find_one(doc! { "email": body.email }).await {
+    let user = match data
+        .users
+        .find_one(doc! { "email": normalize_email(&body.email) })
+        .collation(email_collation())
+        .await
+    {
         Ok(Some(u)) => u,
         Ok(None) => return respond::error(actix_web::http::StatusCode::UNAUTHORIZED, "Invalid credentials"),
         Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
@@ -28,16 +33,9 @@ pub async fn auth_login(
         return respond::error(actix_web::http::StatusCode::UNAUTHORIZED, "Invalid credentials");
     }
 
-    let token = match auth::issue_token(user.id, &data.jwt_secret) {
-        Ok(t) => t,
-        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Token generation failed"),
-    };
-
-    let out = AuthOut {
-        token,
-        user: UserOut::from(UserDb { password_hash: String::new(), ..user }),
-    };
-
-    respond::ok_json(out)
+    match sign_in(&data, &req, user).await {
+        Ok(out) => respond::ok_json(out),
+        Err(e) => e,
+    }
 }
 
@@ -0,0 +1,35 @@
+use actix_web::{post, web, HttpRequest, Responder};
+use mongodb::bson::doc;
+
+use crate::api::respond;
+use crate::auth;
+use crate::models::LogoutIn;
+use crate::server::AppState;
+use crate::sessions;
+
+/// End the current session. Identified by the refresh token when given (works
+/// after the access token has expired), otherwise by the access token.
+#[post("/api/auth/logout")]
+pub async fn auth_logout(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: Option<web::Json<LogoutIn>>,
+) -> impl Responder {
+    let refresh_token = body.and_then(|b| b.into_inner().refresh_token);
+    if let Some(token) = refresh_token.as_deref().filter(|t| !t.trim().is_empty()) {
+        if sessions::end_by_token(&data, token).await.is_err() {
+            return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+        }
+        return respond::ok_json(serde_json::json!({ "ok": true }));
+    }
+
+    let (_, session_id) = match auth::require_session(&req, &data).await {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    if data.sessions.delete_one(doc! { "_id": session_id }).await.is_err() {
+        return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+    }
+
+    respond::ok_json(serde_json::json!({ "ok": true }))
+}
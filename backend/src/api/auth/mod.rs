@@ -1,3 +1,9 @@
+pub mod forgot;
 pub mod login;
+pub mod logout;
+pub mod refresh;
+pub mod reset;
 pub mod signup;
-
+pub mod tokens;
+pub mod verify;
+pub mod verify_resend;
@@ -0,0 +1,38 @@
+use actix_web::{post, web, HttpRequest, Responder};
+use mongodb::bson::doc;
+
+use crate::api::auth::tokens::auth_out;
+use crate::api::respond;
+use crate::models::RefreshIn;
+use crate::server::AppState;
+use crate::sessions;
+
+/// Exchange a refresh token for a new access token. The refresh token is
+/// rotated: the response carries its replacement and the old one stops working.
+#[post("/api/auth/refresh")]
+pub async fn auth_refresh(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<RefreshIn>,
+) -> impl Responder {
+    if body.refresh_token.trim().is_empty() {
+        return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "refreshToken is required");
+    }
+
+    let (session, refresh_token) = match sessions::rotate(&data, &body.refresh_token, sessions::client(&req)).await {
+        Ok(Some(v)) => v,
+        Ok(None) => return respond::error(actix_web::http::StatusCode::UNAUTHORIZED, "Invalid refresh token"),
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    let user = match data.users.find_one(doc! { "_id": session.user_id }).await {
+        Ok(Some(u)) => u,
+        Ok(None) => return respond::error(actix_web::http::StatusCode::UNAUTHORIZED, "Invalid refresh token"),
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    match auth_out(&data, &session, refresh_token, user) {
+        Ok(out) => respond::ok_json(out),
+        Err(e) => e,
+    }
+}
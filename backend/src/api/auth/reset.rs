@@ -0,0 +1,59 @@
+use actix_web::{post, web, HttpRequest, Responder};
+use mongodb::bson::{doc, DateTime};
+
+use crate::api::respond;
+use crate::models::ResetPasswordIn;
+use crate::server::AppState;
+use crate::sessions;
+
+/// Set a new password with a token from `POST /api/auth/forgot`.
+///
+/// The token is consumed even if the rest fails, and every session of the
+/// user is ended (like a password change).
+#[post("/api/auth/reset")]
+pub async fn auth_reset(
+    data: web::Data<AppState>,
+    _req: HttpRequest,
+    body: web::Json<ResetPasswordIn>,
+) -> impl Responder {
+    let body = body.into_inner();
+    if body.password.len() < 8 {
+        return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Password must be at least 8 characters");
+    }
+
+    // deleting on lookup makes the token single-use, even under concurrent requests
+    let reset = match data
+        .password_resets
+        .find_one_and_delete(doc! {
+            "tokenHash": sessions::hash_token(&body.token),
+            "expiresAt": { "$gt": DateTime::now() },
+        })
+        .await
+    {
+        Ok(Some(v)) => v,
+        Ok(None) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid or expired reset link"),
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    let password_hash = match bcrypt::hash(&body.password, bcrypt::DEFAULT_COST) {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Password hashing failed"),
+    };
+    match data
+        .users
+        .update_one(doc! { "_id": reset.user_id }, doc! { "$set": { "password_hash": password_hash } })
+        .await
+    {
+        Ok(r) if r.matched_count == 0 => {
+            return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid or expired reset link")
+        }
+        Ok(_) => {}
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    }
+
+    if data.sessions.delete_many(doc! { "userId": reset.user_id }).await.is_err() {
+        return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+    }
+
+    respond::ok_json(serde_json::json!({ "ok": true }))
+}
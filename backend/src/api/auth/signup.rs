@@ -1,9 +1,11 @@
 use actix_web::{post, web, HttpRequest, Responder};
 use mongodb::bson::{doc, oid::ObjectId};
+use mongodb::error::{ErrorKind, WriteFailure};
 
+use crate::api::auth::tokens::sign_in;
+use crate::api::auth::verify::send_verification;
 use crate::api::respond;
-use crate::auth;
-use crate::models::{AuthOut, SignupIn, UserDb, UserOut};
+use crate::models::{email_collation, normalize_email, SignupIn, UserDb};
 use crate::server::AppState;
 
 fn validate_signup(body: &SignupIn) -> Result<(), &'static str> {
@@ -20,18 +22,28 @@ fn validate_signup(body: &SignupIn) -> Result<(), &'static str> {
     Ok(())
 }
 
+fn is_duplicate_key(e: &mongodb::error::Error) -> bool {
+    matches!(&*e.kind, ErrorKind::Write(WriteFailure::WriteError(w)) if w.code == 11000)
+}
+
 #[post("/api/auth/signup")]
 pub async fn auth_signup(
     data: web::Data<AppState>,
-    _req: HttpRequest,
+    req: HttpRequest,
     body: web::Json<SignupIn>,
 ) -> impl Responder {
-    let body = body.into_inner();
+    let mut body = body.into_inner();
+    body.email = normalize_email(&body.email);
     if let Err(msg) = validate_signup(&body) {
         return respond::error(actix_web::http::StatusCode::BAD_REQUEST, msg);
     }
 
-    let existing = match data.users.find_one(doc! { "email": body.email.clone() }).await {
+    let existing = match data
+        .users
+        .find_one(doc! { "email": &body.email })
+        .collation(email_collation())
+        .await
+    {
         Ok(v) => v,
         Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
     };
@@ -49,22 +61,23 @@ pub async fn auth_signup(
         email: body.email,
         name: body.name,
         password_hash,
+        unverified: true,
     };
 
-    if let Err(_) = data.users.insert_one(&user).await {
-        return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+    match data.users.insert_one(&user).await {
+        Ok(_) => {}
+        // the unique email index caught a concurrent signup
+        Err(e) if is_duplicate_key(&e) => {
+            return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Email already exists")
+        }
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
     }
 
-    let token = match auth::issue_token(user.id, &data.jwt_secret) {
-        Ok(t) => t,
-        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Token generation failed"),
-    };
-
-    let out = AuthOut {
-        token,
-        user: UserOut::from(user),
-    };
+    send_verification(&data, &user);
 
-    respond::created_json(out)
+    match sign_in(&data, &req, user).await {
+        Ok(out) => respond::created_json(out),
+        Err(e) => e,
+    }
 }
 
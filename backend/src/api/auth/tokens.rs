@@ -0,0 +1,33 @@
+use actix_web::{HttpRequest, HttpResponse};
+
+use crate::api::respond;
+use crate::auth;
+use crate::models::{AuthOut, SessionDb, UserDb, UserOut};
+use crate::server::AppState;
+use crate::sessions;
+
+/// Builds the login response for a session: a fresh access token plus its refresh token.
+pub fn auth_out(
+    data: &AppState,
+    session: &SessionDb,
+    refresh_token: String,
+    user: UserDb,
+) -> Result<AuthOut, HttpResponse> {
+    let token = auth::issue_token(user.id, session.id, &data.jwt_secret).map_err(|_| {
+        respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Token generation failed")
+    })?;
+    Ok(AuthOut {
+        token,
+        expires_in: auth::ACCESS_TOKEN_MINUTES * 60,
+        refresh_token,
+        user: UserOut::from(UserDb { password_hash: String::new(), ..user }),
+    })
+}
+
+/// Starts a new session for `user` (login/signup/password change).
+pub async fn sign_in(data: &AppState, req: &HttpRequest, user: UserDb) -> Result<AuthOut, HttpResponse> {
+    let (session, refresh_token) = sessions::start(data, user.id, sessions::client(req))
+        .await
+        .map_err(|_| respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    auth_out(data, &session, refresh_token, user)
+}
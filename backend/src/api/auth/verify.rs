@@ -0,0 +1,57 @@
+use actix_web::{post, web, HttpRequest, Responder};
+use mongodb::bson::doc;
+
+use crate::api::respond;
+use crate::auth;
+use crate::mailer::{self, Email};
+use crate::models::{email_collation, UserDb, VerifyEmailIn};
+use crate::server::AppState;
+
+/// Mails `user` a signed link that confirms their email (in the background).
+pub fn send_verification(data: &AppState, user: &UserDb) {
+    // signing only fails on a broken key setup; the user can ask for a new link
+    let Ok(token) = auth::issue_verify_token(user.id, &user.email, &data.jwt_secret) else {
+        return;
+    };
+    mailer::send_later(
+        data.mailer.clone(),
+        Email {
+            to: user.email.clone(),
+            subject: "Confirm your email".to_string(),
+            body: format!(
+                "Hi {},\n\nOpen this link to confirm your email address:\n{}/verify-email?token={token}\n\n\
+                 The link expires in {} hours.\n",
+                user.name,
+                data.app_url,
+                auth::VERIFY_TOKEN_HOURS,
+            ),
+        },
+    );
+}
+
+/// Confirm an email address with the token from a verification link.
+/// No login needed: the link may be opened on another device.
+#[post("/api/auth/verify")]
+pub async fn auth_verify(
+    data: web::Data<AppState>,
+    _req: HttpRequest,
+    body: web::Json<VerifyEmailIn>,
+) -> impl Responder {
+    let Some((user_id, email)) = auth::decode_verify_token(&body.token, &data.jwt_secret) else {
+        return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid or expired verification link");
+    };
+
+    // the email must still match, so a link for an old address can't verify a new one
+    match data
+        .users
+        .update_one(doc! { "_id": user_id, "email": &email }, doc! { "$set": { "unverified": false } })
+        .collation(email_collation())
+        .await
+    {
+        Ok(r) if r.matched_count == 0 => {
+            respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid or expired verification link")
+        }
+        Ok(_) => respond::ok_json(serde_json::json!({ "ok": true })),
+        Err(_) => respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    }
+}
@@ -0,0 +1,29 @@
+use actix_web::{post, web, HttpRequest, Responder};
+use mongodb::bson::doc;
+
+use crate::api::auth::verify::send_verification;
+use crate::api::respond;
+use crate::auth;
+use crate::server::AppState;
+
+/// Send the current user a new verification link.
+#[post("/api/auth/verify/resend")]
+pub async fn auth_verify_resend(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let user = match data.users.find_one(doc! { "_id": user_id }).await {
+        Ok(Some(u)) => u,
+        Ok(None) => return respond::error(actix_web::http::StatusCode::NOT_FOUND, "User not found"),
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+    if !user.unverified {
+        return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Email is already verified");
+    }
+
+    send_verification(&data, &user);
+
+    respond::ok_json(serde_json::json!({ "ok": true }))
+}
@@ -0,0 +1,49 @@
+use actix_web::{post, web, HttpRequest, Responder};
+use mongodb::bson::{oid::ObjectId, DateTime};
+
+use crate::api::comments::validate_comment;
+use crate::api::issues::access;
+use crate::api::respond;
+use crate::auth;
+use crate::models::{CommentDb, CommentIn, CommentOut};
+use crate::server::AppState;
+
+#[post("/api/issues/{id}/comments")]
+pub async fn comments_create(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    body: web::Json<CommentIn>,
+) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let body = body.into_inner();
+    if let Err(msg) = validate_comment(&body.body) {
+        return respond::error(actix_web::http::StatusCode::BAD_REQUEST, msg);
+    }
+
+    let issue = match access::load_issue_for_member(&data, &path.into_inner(), user_id).await {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let comment = CommentDb {
+        id: ObjectId::new(),
+        issue_id: issue.id,
+        organization_id: issue.organization_id,
+        author_id: user_id,
+        body: body.body.trim().to_string(),
+        created_at: DateTime::now(),
+        edited_at: None,
+        edit_count: 0,
+    };
+
+    if data.comments.insert_one(&comment).await.is_err() {
+        return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+    }
+
+    respond::created_json(CommentOut::from(comment))
+}
@@ -0,0 +1,64 @@
+use actix_web::{delete, web, HttpRequest, Responder};
+use mongodb::bson::{doc, oid::ObjectId};
+
+use crate::api::issues::access;
+use crate::api::respond;
+use crate::auth;
+use crate::server::AppState;
+
+/// Delete a comment (author or organization owner).
+#[delete("/api/issues/{id}/comments/{comment_id}")]
+pub async fn comments_delete(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let (issue_id, comment_id) = path.into_inner();
+    let comment_id = match ObjectId::parse_str(&comment_id) {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid comment id"),
+    };
+
+    let issue = match access::load_issue_for_member(&data, &issue_id, user_id).await {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let comment = match data
+        .comments
+        .find_one(doc! { "_id": comment_id, "issueId": issue.id })
+        .await
+    {
+        Ok(Some(v)) => v,
+        Ok(None) => return respond::error(actix_web::http::StatusCode::NOT_FOUND, "Comment not found"),
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    if comment.author_id != user_id {
+        let is_owner = match data
+            .organizations
+            .find_one(doc! { "_id": issue.organization_id, "ownerId": user_id })
+            .await
+        {
+            Ok(v) => v.is_some(),
+            Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+        };
+        if !is_owner {
+            return respond::error(
+                actix_web::http::StatusCode::FORBIDDEN,
+                "Only the author or the organization owner can delete this comment",
+            );
+        }
+    }
+
+    if data.comments.delete_one(doc! { "_id": comment_id }).await.is_err() {
+        return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+    }
+
+    respond::ok_json(serde_json::json!({ "ok": true }))
+}
@@ -0,0 +1,47 @@
+use actix_web::{get, web, HttpRequest, Responder};
+use futures_util::TryStreamExt;
+use mongodb::bson::doc;
+
+use crate::api::issues::access;
+use crate::api::respond;
+use crate::auth;
+use crate::models::CommentOut;
+use crate::server::AppState;
+
+/// Comments of an issue, oldest first.
+#[get("/api/issues/{id}/comments")]
+pub async fn comments_list(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let issue = match access::load_issue_for_member(&data, &path.into_inner(), user_id).await {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let mut cursor = match data
+        .comments
+        .find(doc! { "issueId": issue.id })
+        .sort(doc! { "_id": 1 })
+        .await
+    {
+        Ok(c) => c,
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    let mut out: Vec<CommentOut> = Vec::new();
+    while let Some(comment) = match cursor.try_next().await {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    } {
+        out.push(CommentOut::from(comment));
+    }
+
+    respond::ok_json(out)
+}
@@ -0,0 +1,17 @@
+pub mod create;
+pub mod delete;
+pub mod list;
+pub mod update;
+
+/// Maximum comment length (characters).
+pub const MAX_COMMENT_LEN: usize = 10_000;
+
+pub fn validate_comment(body: &str) -> Result<(), &'static str> {
+    if body.trim().is_empty() {
+        return Err("body is required");
+    }
+    if body.chars().count() > MAX_COMMENT_LEN {
+        return Err("Comment is too long");
+    }
+    Ok(())
+}
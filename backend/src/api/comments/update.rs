@@ -0,0 +1,72 @@
+use actix_web::{put, web, HttpRequest, Responder};
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use mongodb::options::ReturnDocument;
+
+use crate::api::comments::validate_comment;
+use crate::api::issues::access;
+use crate::api::respond;
+use crate::auth;
+use crate::models::{CommentIn, CommentOut};
+use crate::server::AppState;
+
+/// Edit a comment (author-only). Bumps `editCount` and sets `editedAt`.
+#[put("/api/issues/{id}/comments/{comment_id}")]
+pub async fn comments_update(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    body: web::Json<CommentIn>,
+) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let (issue_id, comment_id) = path.into_inner();
+    let comment_id = match ObjectId::parse_str(&comment_id) {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid comment id"),
+    };
+
+    let body = body.into_inner();
+    if let Err(msg) = validate_comment(&body.body) {
+        return respond::error(actix_web::http::StatusCode::BAD_REQUEST, msg);
+    }
+
+    let issue = match access::load_issue_for_member(&data, &issue_id, user_id).await {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let comment = match data
+        .comments
+        .find_one(doc! { "_id": comment_id, "issueId": issue.id })
+        .await
+    {
+        Ok(Some(v)) => v,
+        Ok(None) => return respond::error(actix_web::http::StatusCode::NOT_FOUND, "Comment not found"),
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    if comment.author_id != user_id {
+        return respond::error(actix_web::http::StatusCode::FORBIDDEN, "Only the author can edit this comment");
+    }
+
+    let update = doc! {
+        "$set": { "body": body.body.trim(), "editedAt": DateTime::now() },
+        "$inc": { "editCount": 1 },
+    };
+
+    let updated = match data
+        .comments
+        .find_one_and_update(doc! { "_id": comment_id }, update)
+        .return_document(ReturnDocument::After)
+        .await
+    {
+        Ok(Some(v)) => v,
+        Ok(None) => return respond::error(actix_web::http::StatusCode::NOT_FOUND, "Comment not found"),
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    respond::ok_json(CommentOut::from(updated))
+}
@@ -0,0 +1,40 @@
+use actix_web::HttpResponse;
+use mongodb::bson::{doc, oid::ObjectId};
+
+use crate::api::respond;
+use crate::models::IssueDb;
+use crate::server::AppState;
+
+/// Loads an issue and checks that `user_id` is a member of its organization.
+///
+/// Returns an `HttpResponse` (400/403/404/500) on failure so handlers can `return e;`.
+pub async fn load_issue_for_member(
+    data: &AppState,
+    issue_id: &str,
+    user_id: ObjectId,
+) -> Result<IssueDb, HttpResponse> {
+    let oid = ObjectId::parse_str(issue_id)
+        .map_err(|_| respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid id"))?;
+
+    let issue = match data.issues.find_one(doc! { "_id": oid }).await {
+        Ok(Some(v)) => v,
+        Ok(None) => return Err(respond::error(actix_web::http::StatusCode::NOT_FOUND, "Issue not found")),
+        Err(_) => return Err(respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error")),
+    };
+
+    // membership check against organizationId from issue
+    let member = match data
+        .organizations
+        .find_one(doc! { "_id": issue.organization_id, "memberIds": user_id })
+        .await
+    {
+        Ok(Some(_)) => true,
+        Ok(None) => false,
+        Err(_) => return Err(respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error")),
+    };
+    if !member {
+        return Err(respond::error(actix_web::http::StatusCode::FORBIDDEN, "Not a member of this organization"));
+    }
+
+    Ok(issue)
+}
@@ -1,36 +1,16 @@
 use actix_web::{post, web, HttpRequest, Responder};
-use mongodb::bson::{doc, oid::ObjectId};
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
 
+use crate::api::issues::fields::{self, validate_issue};
+use crate::api::issues::numbering;
 use crate::api::respond;
 use crate::auth;
-use crate::models::{IssueDb, IssueIn, IssueOut};
+use crate::flow;
+use crate::history;
+use crate::models::{issue_key, IssueDb, IssueIn, IssueOut};
+use crate::rank;
 use crate::server::AppState;
-
-fn normalize_status(status: &str) -> Option<&'static str> {
-    match status {
-        "todo" => Some("todo"),
-        "in_progress" => Some("in_progress"),
-        "in_review" => Some("in_review"),
-        // legacy alias
-        "backlog" => Some("in_review"),
-        "done" => Some("done"),
-        _ => None,
-    }
-}
-
-fn validate_issue(body: &IssueIn) -> Result<(), &'static str> {
-    if body.organization_id.trim().is_empty()
-        || body.title.trim().is_empty()
-        || body.description.trim().is_empty()
-        || body.status.trim().is_empty()
-    {
-        return Err("organizationId, title, description, status are required");
-    }
-    if normalize_status(body.status.trim()).is_none() {
-        return Err("Invalid status");
-    }
-    Ok(())
-}
+use crate::workflow;
 
 #[post("/api/issues")]
 pub async fn issues_create(
@@ -38,7 +18,7 @@ pub async fn issues_create(
     req: HttpRequest,
     body: web::Json<IssueIn>,
 ) -> impl Responder {
-    let user_id = match auth::require_user_id(&req, &data.jwt_secret) {
+    let user_id = match auth::require_user_id(&req, &data).await {
         Ok(u) => u,
         Err(e) => return e,
     };
@@ -83,8 +63,10 @@ pub async fn issues_create(
         None => None,
     };
 
-    let status = match normalize_status(body.status.trim()) {
-        Some(v) => v.to_string(),
+    // status must exist in the organization's workflow
+    let org_workflow = workflow::effective(&org.workflow);
+    let status = match workflow::resolve_status(&org_workflow, body.status.trim()) {
+        Some(v) => v.key.clone(),
         None => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid status"),
     };
 
@@ -109,22 +91,68 @@ pub async fn issues_create(
         if parent.is_none() {
             return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "parentIssueId not found in organization");
         }
+        if let Err(e) = fields::validate_parent(&data, None, pid).await {
+            return e;
+        }
     }
 
+    // already validated by `validate_issue`
+    let priority = match body.priority.as_deref().map(fields::parse_priority) {
+        Some(Ok(v)) => v,
+        Some(Err(msg)) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, msg),
+        None => 0,
+    };
+
+    let label_ids = match fields::resolve_label_ids(&data, org_id, body.label_ids.as_deref().unwrap_or_default()).await {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    // allocate the per-org number last, so rejected requests don't burn numbers
+    let number = match numbering::next_issue_number(&data, org_id).await {
+        Ok(Some(v)) => v,
+        Ok(None) => return respond::error(actix_web::http::StatusCode::NOT_FOUND, "Organization not found"),
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    let now = DateTime::now();
+    let completed_at = workflow::is_completed(&org_workflow, &status).then_some(now);
+    // new issues go to the bottom of the board
+    let rank = match rank::last(&data, org_id).await {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
     let issue = IssueDb {
         id: ObjectId::new(),
         organization_id: org_id,
+        number,
+        key: issue_key(&org.key, number),
         title: body.title,
         description: body.description,
         status,
         assignee_id: assignee_oid,
         parent_issue_id: parent_oid,
+        priority,
+        estimate: body.estimate,
+        // format and order were checked by `validate_issue`
+        start_date: body.start_date.as_deref().and_then(fields::parse_date),
+        due_date: body.due_date.as_deref().and_then(fields::parse_date),
+        label_ids,
+        completed_at,
+        rank,
+        created_at: Some(now),
+        created_by: Some(user_id),
+        updated_at: Some(now),
+        updated_by: Some(user_id),
     };
 
-    if let Err(_) = data.issues.insert_one(&issue).await {
+    if data.issues.insert_one(&issue).await.is_err() {
         return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
     }
 
+    history::record(&data, user_id, None, Some(&issue)).await;
+    flow::record(&data, &issue, None).await;
+
     respond::created_json(IssueOut::from(issue))
 }
 
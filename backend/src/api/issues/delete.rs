@@ -1,54 +1,137 @@
 use actix_web::{delete, web, HttpRequest, Responder};
-use mongodb::bson::{doc, oid::ObjectId};
+use futures_util::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
 
+use crate::api::issues::access;
 use crate::api::respond;
 use crate::auth;
+use crate::hierarchy;
+use crate::history;
+use crate::models::{DeleteIssueQuery, IssueDb};
+use crate::relations;
 use crate::server::AppState;
 
+/// Delete an issue (requires membership). Also deletes its comments.
+///
+/// `?mode=` decides what happens to sub-issues:
+/// - `reparent` (default): children move up to the deleted issue's parent
+/// - `cascade`: the whole subtree is deleted
+/// - `restrict`: refuse (409) while the issue has children
 #[delete("/api/issues/{id}")]
 pub async fn issues_delete(
     data: web::Data<AppState>,
     req: HttpRequest,
     path: web::Path<String>,
+    query: web::Query<DeleteIssueQuery>,
 ) -> impl Responder {
-    let user_id = match auth::require_user_id(&req, &data.jwt_secret) {
+    let user_id = match auth::require_user_id(&req, &data).await {
         Ok(u) => u,
         Err(e) => return e,
     };
 
-    let issue_id = match ObjectId::parse_str(&path.into_inner()) {
+    let mode = query.mode.as_deref().unwrap_or("reparent");
+    if !["reparent", "cascade", "restrict"].contains(&mode) {
+        return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "mode must be reparent, cascade or restrict");
+    }
+
+    let issue = match access::load_issue_for_member(&data, &path.into_inner(), user_id).await {
         Ok(v) => v,
-        Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid id"),
+        Err(e) => return e,
     };
 
-    let issue = match data.issues.find_one(doc! { "_id": issue_id }).await {
-        Ok(Some(v)) => v,
-        Ok(None) => return respond::error(actix_web::http::StatusCode::NOT_FOUND, "Issue not found"),
+    let mut children: Vec<IssueDb> = Vec::new();
+    let mut cursor = match data.issues.find(doc! { "parentIssueId": issue.id }).await {
+        Ok(c) => c,
         Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
     };
-
-    // membership check
-    let member = match data
-        .organizations
-        .find_one(doc! { "_id": issue.organization_id, "memberIds": user_id })
-        .await
-    {
-        Ok(Some(_)) => true,
-        Ok(None) => false,
+    while let Some(child) = match cursor.try_next().await {
+        Ok(v) => v,
         Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
-    };
-    if !member {
-        return respond::error(actix_web::http::StatusCode::FORBIDDEN, "Not a member of this organization");
+    } {
+        children.push(child);
     }
 
-    let deleted = match data.issues.find_one_and_delete(doc! { "_id": issue_id }).await {
-        Ok(v) => v,
+    if mode == "restrict" && !children.is_empty() {
+        return respond::error(
+            actix_web::http::StatusCode::CONFLICT,
+            &format!("Issue has {} sub-issue(s); delete or move them first", children.len()),
+        );
+    }
+
+    // everything that gets deleted: the issue plus (for cascade) its subtree
+    let mut removed: Vec<IssueDb> = vec![issue.clone()];
+    if mode == "cascade" && !children.is_empty() {
+        match hierarchy::descendants(&data, &issue, None).await {
+            Ok(v) => removed.extend(v.into_iter().map(|(d, _)| d)),
+            Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+        }
+    }
+    let removed_ids: Vec<ObjectId> = removed.iter().map(|i| i.id).collect();
+
+    let mut reparented = 0;
+    if mode == "reparent" && !children.is_empty() {
+        match data
+            .issues
+            .update_many(
+                doc! { "parentIssueId": issue.id },
+                doc! { "$set": {
+                    "parentIssueId": issue.parent_issue_id,
+                    "updatedAt": DateTime::now(),
+                    "updatedBy": user_id,
+                } },
+            )
+            .await
+        {
+            Ok(r) => reparented = r.modified_count,
+            Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+        }
+        for child in &children {
+            let moved = IssueDb {
+                parent_issue_id: issue.parent_issue_id,
+                ..child.clone()
+            };
+            history::record(&data, user_id, Some(child), Some(&moved)).await;
+        }
+    }
+
+    let deleted = match data.issues.delete_many(doc! { "_id": { "$in": &removed_ids } }).await {
+        Ok(r) => r.deleted_count,
         Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
     };
-    if deleted.is_none() {
+    if deleted == 0 {
         return respond::error(actix_web::http::StatusCode::NOT_FOUND, "Issue not found");
     }
 
-    respond::ok_json(serde_json::json!({ "ok": true }))
-}
+    if data
+        .comments
+        .delete_many(doc! { "issueId": { "$in": &removed_ids } })
+        .await
+        .is_err()
+    {
+        return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+    }
+
+    if relations::remove_for(&data, &removed_ids).await.is_err() {
+        return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+    }
 
+    if data
+        .status_transitions
+        .delete_many(doc! { "issueId": { "$in": &removed_ids } })
+        .await
+        .is_err()
+    {
+        return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+    }
+
+    for i in &removed {
+        history::record(&data, user_id, Some(i), None).await;
+    }
+
+    respond::ok_json(serde_json::json!({
+        "ok": true,
+        "mode": mode,
+        "deleted": deleted,
+        "reparented": reparented,
+    }))
+}
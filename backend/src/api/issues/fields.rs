@@ -0,0 +1,144 @@
+//! Field validation shared by the issue create/update handlers.
+
+use actix_web::HttpResponse;
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+
+use crate::api::respond;
+use crate::flow::DAY_MS;
+use crate::hierarchy;
+use crate::models::{parse_time, priority_rank, IssueDb, IssueIn};
+use crate::server::AppState;
+
+/// Maximum number of labels on a single issue.
+pub const MAX_LABELS: usize = 20;
+
+/// Upper bound for `estimate` (points/hours, whatever the team uses).
+pub const MAX_ESTIMATE: f64 = 1000.0;
+
+pub fn validate_issue(body: &IssueIn) -> Result<(), &'static str> {
+    if body.organization_id.trim().is_empty()
+        || body.title.trim().is_empty()
+        || body.description.trim().is_empty()
+        || body.status.trim().is_empty()
+    {
+        return Err("organizationId, title, description, status are required");
+    }
+    if let Some(p) = &body.priority {
+        parse_priority(p)?;
+    }
+    if let Some(e) = body.estimate {
+        validate_estimate(e)?;
+    }
+    let start = match body.start_date.as_deref() {
+        Some(d) => Some(parse_date(d).ok_or("Invalid startDate (expected YYYY-MM-DD)")?),
+        None => None,
+    };
+    let due = match body.due_date.as_deref() {
+        Some(d) => Some(parse_date(d).ok_or("Invalid dueDate (expected YYYY-MM-DD)")?),
+        None => None,
+    };
+    validate_dates(start, due)?;
+    Ok(())
+}
+
+/// Parses a calendar date (`YYYY-MM-DD`; a full RFC 3339 time is cut to its UTC day).
+pub fn parse_date(value: &str) -> Option<DateTime> {
+    let t = parse_time(value)?.timestamp_millis();
+    Some(DateTime::from_millis(t - t.rem_euclid(DAY_MS)))
+}
+
+/// The due date may not be before the start date (the same day is fine).
+pub fn validate_dates(start: Option<DateTime>, due: Option<DateTime>) -> Result<(), &'static str> {
+    if let (Some(start), Some(due)) = (start, due)
+        && due < start
+    {
+        return Err("dueDate must not be before startDate");
+    }
+    Ok(())
+}
+
+/// Parses a priority name (`none`, `low`, `medium`, `high`, `urgent`) into its stored rank.
+pub fn parse_priority(value: &str) -> Result<i32, &'static str> {
+    priority_rank(&value.trim().to_lowercase()).ok_or("Invalid priority (expected none, low, medium, high or urgent)")
+}
+
+pub fn validate_estimate(value: f64) -> Result<(), &'static str> {
+    if !value.is_finite() || !(0.0..=MAX_ESTIMATE).contains(&value) {
+        return Err("estimate must be a number between 0 and 1000");
+    }
+    Ok(())
+}
+
+/// Parses `labelIds` and checks that every label belongs to `org_id`.
+///
+/// Returns an `HttpResponse` on failure so handlers can `return e;`.
+pub async fn resolve_label_ids(
+    data: &AppState,
+    org_id: ObjectId,
+    ids: &[String],
+) -> Result<Vec<ObjectId>, HttpResponse> {
+    let mut out: Vec<ObjectId> = Vec::new();
+    for id in ids {
+        let oid = ObjectId::parse_str(id.trim())
+            .map_err(|_| respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid labelIds"))?;
+        if !out.contains(&oid) {
+            out.push(oid);
+        }
+    }
+    if out.len() > MAX_LABELS {
+        return Err(respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Too many labels"));
+    }
+    if out.is_empty() {
+        return Ok(out);
+    }
+
+    let found = data
+        .labels
+        .count_documents(doc! { "_id": { "$in": &out }, "organizationId": org_id })
+        .await
+        .map_err(|_| respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    if found != out.len() as u64 {
+        return Err(respond::error(
+            actix_web::http::StatusCode::BAD_REQUEST,
+            "labelIds must belong to the issue's organization",
+        ));
+    }
+    Ok(out)
+}
+
+/// Checks that `parent_id` may become the parent of `issue` (`None` while creating).
+///
+/// Rejects self-parenting, cycles, and trees deeper than `AppState::max_issue_depth`
+/// (counting the subtree that moves along with `issue`).
+pub async fn validate_parent(data: &AppState, issue: Option<&IssueDb>, parent_id: ObjectId) -> Result<(), HttpResponse> {
+    let db_error = |_| respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+    let max_depth = data.max_issue_depth;
+
+    if issue.is_some_and(|i| i.id == parent_id) {
+        return Err(respond::error(actix_web::http::StatusCode::BAD_REQUEST, "An issue cannot be its own parent"));
+    }
+
+    let ancestors = hierarchy::ancestor_ids(data, parent_id, max_depth).await.map_err(db_error)?;
+    if let Some(i) = issue
+        && ancestors.contains(&i.id)
+    {
+        return Err(respond::error(
+            actix_web::http::StatusCode::BAD_REQUEST,
+            "parentIssueId would create a cycle",
+        ));
+    }
+
+    // top-level issues are at depth 1
+    let parent_depth = ancestors.len() + 1;
+    let height = match issue {
+        Some(i) => hierarchy::subtree_height(data, i, max_depth).await.map_err(db_error)?,
+        None => 0,
+    };
+    if parent_depth + 1 + height > max_depth {
+        return Err(respond::error(
+            actix_web::http::StatusCode::BAD_REQUEST,
+            &format!("Sub-issues can be nested at most {max_depth} levels deep"),
+        ));
+    }
+    Ok(())
+}
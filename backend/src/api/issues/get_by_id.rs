@@ -1,9 +1,10 @@
 use actix_web::{get, web, HttpRequest, Responder};
-use mongodb::bson::{doc, oid::ObjectId};
 
+use crate::api::issues::access;
 use crate::api::respond;
 use crate::auth;
-use crate::models::IssueOut;
+use crate::models::{IssueDetailOut, IssueOut};
+use crate::relations;
 use crate::server::AppState;
 
 #[get("/api/issues/{id}")]
@@ -12,36 +13,23 @@ pub async fn issues_get_by_id(
     req: HttpRequest,
     id: web::Path<String>,
 ) -> impl Responder {
-    let user_id = match auth::require_user_id(&req, &data.jwt_secret) {
+    let user_id = match auth::require_user_id(&req, &data).await {
         Ok(u) => u,
         Err(e) => return e,
     };
 
-    let oid = match ObjectId::parse_str(&id.into_inner()) {
+    let issue = match access::load_issue_for_member(&data, &id.into_inner(), user_id).await {
         Ok(v) => v,
-        Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid id"),
-    };
-
-    let issue = match data.issues.find_one(doc! { "_id": oid }).await {
-        Ok(Some(v)) => v,
-        Ok(None) => return respond::error(actix_web::http::StatusCode::NOT_FOUND, "Issue not found"),
-        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+        Err(e) => return e,
     };
 
-    // membership check against organizationId from issue
-    let member = match data
-        .organizations
-        .find_one(doc! { "_id": issue.organization_id, "memberIds": user_id })
-        .await
-    {
-        Ok(Some(_)) => true,
-        Ok(None) => false,
+    let relations = match relations::for_issue(&data, issue.id).await {
+        Ok(v) => v,
         Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
     };
-    if !member {
-        return respond::error(actix_web::http::StatusCode::FORBIDDEN, "Not a member of this organization");
-    }
 
-    respond::ok_json(IssueOut::from(issue))
+    respond::ok_json(IssueDetailOut {
+        issue: IssueOut::from(issue),
+        relations,
+    })
 }
-
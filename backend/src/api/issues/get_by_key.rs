@@ -0,0 +1,58 @@
+use actix_web::{get, web, HttpRequest, Responder};
+use mongodb::bson::doc;
+
+use crate::api::respond;
+use crate::auth;
+use crate::models::{IssueDetailOut, IssueOut};
+use crate::relations;
+use crate::server::AppState;
+
+/// Look up an issue by its human-readable key, e.g. `/api/organizations/ACME/issues/42`.
+#[get("/api/organizations/{key}/issues/{number}")]
+pub async fn issues_get_by_key(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let (org_key, number) = path.into_inner();
+    let number: i64 = match number.trim().parse() {
+        Ok(v) if v > 0 => v,
+        _ => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid issue number"),
+    };
+
+    // membership check (org is addressed by key here, not by id)
+    let org = match data
+        .organizations
+        .find_one(doc! { "key": org_key.trim().to_uppercase(), "memberIds": user_id })
+        .await
+    {
+        Ok(Some(v)) => v,
+        Ok(None) => return respond::error(actix_web::http::StatusCode::NOT_FOUND, "Organization not found"),
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    let issue = match data
+        .issues
+        .find_one(doc! { "organizationId": org.id, "number": number })
+        .await
+    {
+        Ok(Some(v)) => v,
+        Ok(None) => return respond::error(actix_web::http::StatusCode::NOT_FOUND, "Issue not found"),
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    let relations = match relations::for_issue(&data, issue.id).await {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    respond::ok_json(IssueDetailOut {
+        issue: IssueOut::from(issue),
+        relations,
+    })
+}
@@ -1,11 +1,16 @@
-use actix_web::{get, web, HttpRequest, Responder};
+use actix_web::{get, web, HttpRequest, HttpResponse, Responder};
 use futures_util::TryStreamExt;
-use mongodb::bson::{doc, oid::ObjectId};
+use mongodb::bson::{self, doc, oid::ObjectId, Bson, DateTime, Document};
 
+use crate::api::issues::fields;
 use crate::api::respond;
 use crate::auth;
-use crate::models::{IssueOut, ListIssuesQuery};
+use crate::filter;
+use crate::flow::DAY_MS;
+use crate::models::{IssueDb, IssueOut, LabelDb, ListIssuesQuery, OrganizationDb, PageOut};
+use crate::pagination;
 use crate::server::AppState;
+use crate::workflow;
 
 #[get("/api/issues")]
 pub async fn issues_list(
@@ -13,7 +18,7 @@ pub async fn issues_list(
     req: HttpRequest,
     query: web::Query<ListIssuesQuery>,
 ) -> impl Responder {
-    let user_id = match auth::require_user_id(&req, &data.jwt_secret) {
+    let user_id = match auth::require_user_id(&req, &data).await {
         Ok(u) => u,
         Err(e) => return e,
     };
@@ -26,21 +31,81 @@ pub async fn issues_list(
         Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid organizationId"),
     };
 
-    // membership check
-    let member = match data
+    // membership check (and load org for its workflow)
+    let org = match data
         .organizations
         .find_one(doc! { "_id": org_id, "memberIds": user_id })
         .await
     {
-        Ok(Some(_)) => true,
-        Ok(None) => false,
+        Ok(Some(v)) => v,
+        Ok(None) => return respond::error(actix_web::http::StatusCode::FORBIDDEN, "Not a member of this organization"),
         Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
     };
-    if !member {
-        return respond::error(actix_web::http::StatusCode::FORBIDDEN, "Not a member of this organization");
+
+    list_issues(&data, user_id, &org, &query).await
+}
+
+/// Order used when a request gives `sort` but no `order`: board rank reads top-down.
+pub fn default_order(sort: &str) -> &'static str {
+    if sort == "rank" { "asc" } else { "desc" }
+}
+
+/// Validates `sort`/`order` and returns `(field, direction, cursor key)`.
+pub fn sort_spec(sort: Option<&str>, order: Option<&str>) -> Result<(&'static str, i32, String), &'static str> {
+    let sort_by = sort.unwrap_or("created");
+    let direction = match order.unwrap_or(default_order(sort_by)) {
+        "desc" => -1,
+        "asc" => 1,
+        _ => return Err("order must be asc or desc"),
+    };
+    let field = match sort_by {
+        "created" => "_id",
+        "priority" => "priority",
+        "estimate" => "estimate",
+        "rank" => "rank",
+        "due" => "dueDate",
+        _ => return Err("sort must be created, priority, estimate, rank or due"),
+    };
+    Ok((field, direction, format!("{sort_by}:{direction}")))
+}
+
+/// Parses and compiles a filter expression against an organization's workflow and labels.
+pub async fn compile_filter(
+    data: &AppState,
+    user_id: ObjectId,
+    org: &OrganizationDb,
+    raw: &str,
+) -> Result<Vec<Document>, HttpResponse> {
+    let parsed = filter::parse(raw)
+        .map_err(|e| respond::error(actix_web::http::StatusCode::BAD_REQUEST, &e.to_string()))?;
+    let db_error = |_| respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+
+    let mut labels: Vec<LabelDb> = Vec::new();
+    if parsed.uses(filter::Field::Label) {
+        let mut cursor = data.labels.find(doc! { "organizationId": org.id }).await.map_err(db_error)?;
+        while let Some(label) = cursor.try_next().await.map_err(db_error)? {
+            labels.push(label);
+        }
     }
 
-    let mut filter = doc! { "organizationId": org_id };
+    let org_workflow = workflow::effective(&org.workflow);
+    let ctx = filter::FilterContext {
+        user_id,
+        workflow: &org_workflow,
+        labels: &labels,
+    };
+    filter::compile(&parsed, &ctx).map_err(|e| respond::error(actix_web::http::StatusCode::BAD_REQUEST, &e.to_string()))
+}
+
+/// Lists one page of issues of `org`; membership must already be checked.
+/// Shared by `GET /api/issues` and saved views.
+pub async fn list_issues(
+    data: &AppState,
+    user_id: ObjectId,
+    org: &OrganizationDb,
+    query: &ListIssuesQuery,
+) -> HttpResponse {
+    let mut filter = doc! { "organizationId": org.id };
     if let Some(parent_str) = query.parent_issue_id.clone() {
         let parent_oid = match ObjectId::parse_str(&parent_str) {
             Ok(v) => v,
@@ -49,19 +114,150 @@ pub async fn issues_list(
         filter.insert("parentIssueId", parent_oid);
     }
 
-    let mut cursor = match data.issues.find(filter).sort(doc! { "_id": -1 }).await {
+    if let Some(raw) = query.priority.as_deref() {
+        let mut ranks: Vec<Bson> = Vec::new();
+        for name in raw.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            match fields::parse_priority(name) {
+                Ok(v) => {
+                    ranks.push(Bson::Int32(v));
+                    // issues created before priorities existed have no field at all
+                    if v == 0 {
+                        ranks.push(Bson::Null);
+                    }
+                }
+                Err(msg) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, msg),
+            }
+        }
+        if !ranks.is_empty() {
+            filter.insert("priority", doc! { "$in": ranks });
+        }
+    }
+
+    let mut estimate = doc! {};
+    if let Some(min) = query.estimate_min {
+        estimate.insert("$gte", min);
+    }
+    if let Some(max) = query.estimate_max {
+        estimate.insert("$lte", max);
+    }
+    if !estimate.is_empty() {
+        filter.insert("estimate", estimate);
+    }
+
+    if let Some(label_str) = query.label_id.clone() {
+        let label_oid = match ObjectId::parse_str(&label_str) {
+            Ok(v) => v,
+            Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid labelId"),
+        };
+        filter.insert("labelIds", label_oid);
+    }
+
+    let mut due = doc! {};
+    for (raw, op, name) in [
+        (query.due_before.as_deref(), "$lt", "dueBefore"),
+        (query.due_after.as_deref(), "$gt", "dueAfter"),
+    ] {
+        if let Some(raw) = raw {
+            let Some(d) = fields::parse_date(raw) else {
+                return respond::error(
+                    actix_web::http::StatusCode::BAD_REQUEST,
+                    &format!("Invalid {name} (expected YYYY-MM-DD)"),
+                );
+            };
+            due.insert(op, d);
+        }
+    }
+    if !due.is_empty() {
+        filter.insert("dueDate", due);
+    }
+
+    // overdue: the due day is over and the issue is still open
+    if let Some(overdue) = query.overdue {
+        let now = DateTime::now().timestamp_millis();
+        let today = DateTime::from_millis(now - now.rem_euclid(DAY_MS));
+        let closed = workflow::closed_keys(&workflow::effective(&org.workflow));
+        let condition = if overdue {
+            doc! { "dueDate": { "$lt": today }, "status": { "$nin": closed } }
+        } else {
+            doc! { "$or": [
+                { "dueDate": { "$not": { "$lt": today } } },
+                { "status": { "$in": closed } },
+            ] }
+        };
+        filter = doc! { "$and": [filter, condition] };
+    }
+
+    if let Some(raw) = query.filter.as_deref() {
+        let conditions = match compile_filter(data, user_id, org, raw).await {
+            Ok(v) => v,
+            Err(e) => return e,
+        };
+        if !conditions.is_empty() {
+            let mut all = vec![filter];
+            all.extend(conditions);
+            filter = doc! { "$and": all };
+        }
+    }
+
+    let (sort_field, direction, sort_key) = match sort_spec(query.sort.as_deref(), query.order.as_deref()) {
+        Ok(v) => v,
+        Err(msg) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, msg),
+    };
+    // `_id` breaks ties so the order is stable across pages
+    let sort = if sort_field == "_id" {
+        doc! { "_id": direction }
+    } else {
+        doc! { sort_field: direction, "_id": direction }
+    };
+
+    if let Some(after) = query.after.as_deref() {
+        let Some(c) = pagination::decode(&sort_key, after) else {
+            return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid cursor");
+        };
+        filter = doc! { "$and": [filter, pagination::after_filter(sort_field, direction, &c)] };
+    }
+
+    let limit = pagination::limit(query.limit);
+
+    // raw documents, so the cursor sees exactly what MongoDB sorted on (e.g. missing fields)
+    let mut cursor = match data
+        .issues
+        .clone_with_type::<Document>()
+        .find(filter)
+        .sort(sort)
+        .limit(limit + 1)
+        .await
+    {
         Ok(c) => c,
         Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
     };
 
-    let mut out: Vec<IssueOut> = Vec::new();
-    while let Some(issue) = match cursor.try_next().await {
+    let mut docs: Vec<Document> = Vec::new();
+    while let Some(d) = match cursor.try_next().await {
         Ok(v) => v,
         Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
     } {
-        out.push(IssueOut::from(issue));
+        docs.push(d);
     }
 
-    respond::ok_json(out)
-}
+    let mut next_cursor = None;
+    if docs.len() as i64 > limit {
+        docs.truncate(limit as usize);
+        if let Some(last) = docs.last()
+            && let Ok(id) = last.get_object_id("_id")
+        {
+            let value = last.get(sort_field).cloned().unwrap_or(Bson::Null);
+            next_cursor = Some(pagination::encode(&sort_key, value, id));
+        }
+    }
 
+    let mut items: Vec<IssueOut> = Vec::with_capacity(docs.len());
+    for d in docs {
+        match bson::from_document::<IssueDb>(d) {
+            Ok(issue) => items.push(IssueOut::from(issue)),
+            Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+        }
+    }
+
+    respond::ok_json(PageOut { items, next_cursor })
+}
@@ -1,7 +1,14 @@
+pub mod access;
 pub mod create;
 pub mod delete;
+pub mod fields;
 pub mod get_by_id;
+pub mod get_by_key;
+pub mod history;
 pub mod list;
+pub mod move_issue;
+pub mod numbering;
+pub mod patch;
 pub mod search;
+pub mod tree;
 pub mod update;
-
@@ -0,0 +1,168 @@
+use actix_web::{post, web, HttpRequest, HttpResponse, Responder};
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use mongodb::options::ReturnDocument;
+
+use crate::api::issues::access::load_issue_for_member;
+use crate::api::issues::update::apply_changes;
+use crate::api::respond;
+use crate::auth;
+use crate::models::{IssueDb, IssueMoveIn, IssueOut, IssuePatchIn};
+use crate::rank;
+use crate::server::AppState;
+
+/// Loads a neighbour named in the request; it must be another issue of the same organization.
+async fn load_neighbour(data: &AppState, issue: &IssueDb, id: &str, field: &str) -> Result<IssueDb, HttpResponse> {
+    let oid = ObjectId::parse_str(id.trim())
+        .map_err(|_| respond::error(actix_web::http::StatusCode::BAD_REQUEST, &format!("Invalid {field}")))?;
+    if oid == issue.id {
+        return Err(respond::error(
+            actix_web::http::StatusCode::BAD_REQUEST,
+            &format!("{field} cannot be the issue itself"),
+        ));
+    }
+    match data
+        .issues
+        .find_one(doc! { "_id": oid, "organizationId": issue.organization_id })
+        .await
+    {
+        Ok(Some(v)) => Ok(v),
+        Ok(None) => Err(respond::error(
+            actix_web::http::StatusCode::BAD_REQUEST,
+            &format!("{field} issue not found in this organization"),
+        )),
+        Err(_) => Err(respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error")),
+    }
+}
+
+/// The issue currently next to `neighbour` in rank order (skipping the moved issue).
+async fn adjacent(
+    data: &AppState,
+    issue: &IssueDb,
+    neighbour: &IssueDb,
+    below: bool,
+) -> mongodb::error::Result<Option<IssueDb>> {
+    let (op, dir) = if below { ("$gt", 1) } else { ("$lt", -1) };
+    data.issues
+        .find_one(doc! {
+            "organizationId": issue.organization_id,
+            "_id": { "$ne": issue.id },
+            "rank": { op: &neighbour.rank },
+        })
+        .sort(doc! { "rank": dir })
+        .await
+}
+
+/// Picks a rank between the two neighbours, or `None` if they leave no usable room.
+fn pick(after: Option<&IssueDb>, before: Option<&IssueDb>) -> Option<String> {
+    // unranked (legacy) neighbours can't be placed against
+    if after.is_some_and(|i| i.rank.is_empty()) || before.is_some_and(|i| i.rank.is_empty()) {
+        return None;
+    }
+    let lower = after.map(|i| i.rank.as_str()).unwrap_or_default();
+    rank::between(lower, before.map(|i| i.rank.as_str())).filter(|r| r.len() <= rank::MAX_LEN)
+}
+
+/// Reorders an issue on the board: it ends up directly below `after` and above
+/// `before`, optionally changing its status in the same request.
+///
+/// Giving just one neighbour places the issue right next to it; giving none moves it to the bottom.
+#[post("/api/issues/{id}/move")]
+pub async fn issues_move(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    body: web::Json<IssueMoveIn>,
+) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let issue = match load_issue_for_member(&data, &path.into_inner(), user_id).await {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let body = body.into_inner();
+
+    let mut new_rank = None;
+    // second pass runs after a rebalance, with the neighbours' fresh ranks
+    for attempt in 0..2 {
+        let mut after = match body.after.as_deref() {
+            Some(id) => match load_neighbour(&data, &issue, id, "after").await {
+                Ok(v) => Some(v),
+                Err(e) => return e,
+            },
+            None => None,
+        };
+        let mut before = match body.before.as_deref() {
+            Some(id) => match load_neighbour(&data, &issue, id, "before").await {
+                Ok(v) => Some(v),
+                Err(e) => return e,
+            },
+            None => None,
+        };
+
+        // checked up front: bad input must not cost a rebalance of the whole organization
+        // (unranked issues sort first, so only two unranked neighbours leave the order open)
+        if let (Some(a), Some(b)) = (&after, &before)
+            && !(a.rank.is_empty() && b.rank.is_empty())
+            && a.rank >= b.rank
+        {
+            return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "after must be ranked above before");
+        }
+
+        if after.is_none() && before.is_none() {
+            new_rank = match rank::last(&data, issue.organization_id).await {
+                Ok(v) => Some(v),
+                Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+            };
+        } else {
+            // fill in the missing side so the issue lands right next to the given neighbour
+            let found = match (&after, &before) {
+                (Some(a), None) if !a.rank.is_empty() => adjacent(&data, &issue, a, true).await.map(|v| before = v),
+                (None, Some(b)) if !b.rank.is_empty() => adjacent(&data, &issue, b, false).await.map(|v| after = v),
+                _ => Ok(()),
+            };
+            if found.is_err() {
+                return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+            }
+            new_rank = pick(after.as_ref(), before.as_ref());
+        }
+
+        if new_rank.is_some() || attempt == 1 {
+            break;
+        }
+        if rank::rebalance(&data, issue.organization_id).await.is_err() {
+            return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+        }
+    }
+    let Some(new_rank) = new_rank else {
+        return respond::error(actix_web::http::StatusCode::CONFLICT, "Could not place the issue; try again");
+    };
+
+    // status goes through the regular update path (workflow rules, history, transitions)
+    if let Some(status) = body.status {
+        let changes = IssuePatchIn {
+            status: Some(status),
+            ..Default::default()
+        };
+        let resp = apply_changes(&data, user_id, issue.id, changes).await;
+        if !resp.status().is_success() {
+            return resp;
+        }
+    }
+
+    match data
+        .issues
+        .find_one_and_update(
+            doc! { "_id": issue.id },
+            doc! { "$set": { "rank": new_rank, "updatedAt": DateTime::now(), "updatedBy": user_id } },
+        )
+        .return_document(ReturnDocument::After)
+        .await
+    {
+        Ok(Some(v)) => respond::ok_json(IssueOut::from(v)),
+        Ok(None) => respond::error(actix_web::http::StatusCode::NOT_FOUND, "Issue not found"),
+        Err(_) => respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    }
+}
@@ -0,0 +1,17 @@
+use mongodb::bson::{doc, oid::ObjectId};
+use mongodb::options::ReturnDocument;
+
+use crate::server::AppState;
+
+/// Atomically allocates the next issue number for an organization.
+///
+/// Uses `$inc` on `organizations.issueCounter`, so concurrent creates never
+/// receive the same number. Returns `Ok(None)` if the organization is gone.
+pub async fn next_issue_number(data: &AppState, org_id: ObjectId) -> mongodb::error::Result<Option<i64>> {
+    let org = data
+        .organizations
+        .find_one_and_update(doc! { "_id": org_id }, doc! { "$inc": { "issueCounter": 1_i64 } })
+        .return_document(ReturnDocument::After)
+        .await?;
+    Ok(org.map(|o| o.issue_counter))
+}
@@ -0,0 +1,30 @@
+use actix_web::{patch, web, HttpRequest, Responder};
+use mongodb::bson::oid::ObjectId;
+
+use crate::api::issues::update::apply_changes;
+use crate::api::respond;
+use crate::auth;
+use crate::models::IssuePatchIn;
+use crate::server::AppState;
+
+/// Partial update: only the fields present in the body are changed.
+/// `assigneeId`, `parentIssueId` and `estimate` can be cleared with an explicit `null`.
+#[patch("/api/issues/{id}")]
+pub async fn issues_patch(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    body: web::Json<IssuePatchIn>,
+) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let issue_id = match ObjectId::parse_str(path.into_inner()) {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid id"),
+    };
+
+    apply_changes(&data, user_id, issue_id, body.into_inner()).await
+}
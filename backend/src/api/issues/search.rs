@@ -1,19 +1,24 @@
-use actix_web::{get, web, HttpRequest, Responder};
+use actix_web::{get, web, HttpRequest, HttpResponse, Responder};
 use futures_util::TryStreamExt;
-use mongodb::bson::{doc, oid::ObjectId};
+use mongodb::bson::{self, doc, oid::ObjectId, Bson, Document};
 
 use crate::api::respond;
 use crate::auth;
-use crate::models::{IssueOut, SearchQuery};
+use crate::models::{IssueDb, IssueOut, PageOut, SearchHighlightsOut, SearchHitOut, SearchQuery};
+use crate::pagination;
+use crate::search;
 use crate::server::AppState;
 
+/// Max issues scored in memory by the prefix/fuzzy fallback.
+const FALLBACK_CANDIDATES: i64 = 500;
+
 #[get("/api/issues/search")]
 pub async fn issues_search(
     data: web::Data<AppState>,
     req: HttpRequest,
     query: web::Query<SearchQuery>,
 ) -> impl Responder {
-    let user_id = match auth::require_user_id(&req, &data.jwt_secret) {
+    let user_id = match auth::require_user_id(&req, &data).await {
         Ok(u) => u,
         Err(e) => return e,
     };
@@ -21,7 +26,7 @@ pub async fn issues_search(
     let q = query.q.clone().unwrap_or_default();
     let q = q.trim().to_string();
     if q.is_empty() {
-        return respond::ok_json(Vec::<IssueOut>::new());
+        return respond::ok_json(PageOut::<SearchHitOut> { items: Vec::new(), next_cursor: None });
     }
 
     let Some(org_id_str) = query.organization_id.clone() else {
@@ -46,26 +51,154 @@ pub async fn issues_search(
         return respond::error(actix_web::http::StatusCode::FORBIDDEN, "Not a member of this organization");
     }
 
-    let mut cursor = match data
-        .issues
-        .find(doc! { "$text": { "$search": q }, "organizationId": org_id })
-        .sort(doc! { "score": { "$meta": "textScore" } })
-        .projection(doc! { "score": { "$meta": "textScore" } })
-        .limit(50)
-        .await
-    {
-        Ok(c) => c,
-        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    match search_issues(&data, doc! { "organizationId": org_id }, &q, query.after.as_deref(), query.limit).await {
+        Ok(page) => respond::ok_json(page),
+        Err(e) => e,
+    }
+}
+
+/// Searches issues matching `scope` for `q`; membership must already be checked.
+///
+/// Uses the text index first. Only when that finds nothing on the first page
+/// does it fall back to prefix/fuzzy matching; the cursor remembers which mode
+/// a result set came from.
+pub async fn search_issues(
+    data: &AppState,
+    scope: Document,
+    q: &str,
+    after: Option<&str>,
+    limit: Option<i64>,
+) -> Result<PageOut<SearchHitOut>, HttpResponse> {
+    let terms = search::terms(q);
+    let limit = pagination::limit(limit);
+
+    let text_cursor = after.and_then(|a| pagination::decode("score", a));
+    let fallback_cursor = after.and_then(|a| pagination::decode("fuzzy", a));
+    if after.is_some() && text_cursor.is_none() && fallback_cursor.is_none() {
+        return Err(respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid cursor"));
+    }
+
+    if fallback_cursor.is_none() {
+        let page = text_search(data, scope.clone(), q, &terms, text_cursor, limit).await?;
+        if !page.items.is_empty() || after.is_some() || terms.is_empty() {
+            return Ok(page);
+        }
+    }
+    // a `fuzzy` cursor skips the text search above, so a query without terms can get here
+    if terms.is_empty() {
+        return Ok(PageOut { items: Vec::new(), next_cursor: None });
+    }
+    fallback_search(data, scope, &terms, fallback_cursor, limit).await
+}
+
+fn hit(issue: IssueDb, score: f64, match_type: &'static str, terms: &[String]) -> SearchHitOut {
+    let highlights = SearchHighlightsOut {
+        title: search::highlight(&issue.title, terms, false),
+        description: search::highlight(&issue.description, terms, true),
     };
+    SearchHitOut {
+        issue: IssueOut::from(issue),
+        score,
+        match_type,
+        highlights,
+    }
+}
 
-    let mut out: Vec<IssueOut> = Vec::new();
-    while let Some(issue) = match cursor.try_next().await {
-        Ok(v) => v,
-        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
-    } {
-        out.push(IssueOut::from(issue));
+async fn text_search(
+    data: &AppState,
+    scope: Document,
+    q: &str,
+    terms: &[String],
+    after: Option<pagination::Cursor>,
+    limit: i64,
+) -> Result<PageOut<SearchHitOut>, HttpResponse> {
+    let db_error = |_| respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+
+    let mut matcher = scope;
+    matcher.insert("$text", doc! { "$search": q });
+    let mut pipeline = vec![
+        doc! { "$match": matcher },
+        doc! { "$addFields": { "score": { "$meta": "textScore" } } },
+    ];
+    if let Some(c) = after {
+        pipeline.push(doc! { "$match": pagination::after_filter("score", -1, &c) });
+    }
+    // `_id` breaks ties between equal scores so pages are stable
+    pipeline.push(doc! { "$sort": { "score": -1, "_id": -1 } });
+    pipeline.push(doc! { "$limit": limit + 1 });
+
+    let mut cursor = data.issues.aggregate(pipeline).await.map_err(db_error)?;
+    let mut docs: Vec<Document> = Vec::new();
+    while let Some(d) = cursor.try_next().await.map_err(db_error)? {
+        docs.push(d);
+    }
+
+    let mut next_cursor = None;
+    if docs.len() as i64 > limit {
+        docs.truncate(limit as usize);
+        if let Some(last) = docs.last()
+            && let Ok(id) = last.get_object_id("_id")
+        {
+            let score = last.get("score").cloned().unwrap_or(Bson::Null);
+            next_cursor = Some(pagination::encode("score", score, id));
+        }
     }
 
-    respond::ok_json(out)
+    let mut items: Vec<SearchHitOut> = Vec::with_capacity(docs.len());
+    for d in docs {
+        let score = d.get_f64("score").unwrap_or(0.0);
+        let issue = bson::from_document::<IssueDb>(d)
+            .map_err(|_| respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+        items.push(hit(issue, score, "text", terms));
+    }
+
+    Ok(PageOut { items, next_cursor })
 }
 
+async fn fallback_search(
+    data: &AppState,
+    scope: Document,
+    terms: &[String],
+    after: Option<pagination::Cursor>,
+    limit: i64,
+) -> Result<PageOut<SearchHitOut>, HttpResponse> {
+    let db_error = |_| respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+
+    let filter = doc! { "$and": [scope, search::candidate_filter(terms)] };
+    let mut cursor = data
+        .issues
+        .find(filter)
+        .sort(doc! { "_id": -1 })
+        .limit(FALLBACK_CANDIDATES)
+        .await
+        .map_err(db_error)?;
+
+    let mut scored: Vec<(f64, bool, IssueDb)> = Vec::new();
+    while let Some(issue) = cursor.try_next().await.map_err(db_error)? {
+        if let Some((score, fuzzy)) = search::fallback_score(&issue, terms) {
+            scored.push((score, fuzzy, issue));
+        }
+    }
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0).then(b.2.id.cmp(&a.2.id)));
+
+    // same keyset rule as the database cursors, applied in memory
+    if let Some(c) = after {
+        let score = c.value.as_f64().unwrap_or(f64::INFINITY);
+        scored.retain(|(s, _, issue)| *s < score || (*s == score && issue.id < c.id));
+    }
+
+    let mut next_cursor = None;
+    if scored.len() as i64 > limit {
+        scored.truncate(limit as usize);
+        if let Some((score, _, last)) = scored.last() {
+            next_cursor = Some(pagination::encode("fuzzy", Bson::Double(*score), last.id));
+        }
+    }
+
+    let items = scored
+        .into_iter()
+        .map(|(score, fuzzy, issue)| hit(issue, score, if fuzzy { "fuzzy" } else { "prefix" }, terms))
+        .collect();
+
+    Ok(PageOut { items, next_cursor })
+}
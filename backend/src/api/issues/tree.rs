@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+
+use actix_web::{get, web, HttpRequest, Responder};
+use mongodb::bson::oid::ObjectId;
+
+use crate::api::issues::access;
+use crate::api::respond;
+use crate::auth;
+use crate::hierarchy;
+use crate::models::{IssueDb, IssueOut, IssueTreeOut};
+use crate::server::AppState;
+
+fn build(issue: IssueDb, children_of: &mut HashMap<ObjectId, Vec<IssueDb>>) -> IssueTreeOut {
+    let mut children = children_of.remove(&issue.id).unwrap_or_default();
+    children.sort_by_key(|c| (c.number, c.id));
+    IssueTreeOut {
+        issue: IssueOut::from(issue),
+        children: children.into_iter().map(|c| build(c, children_of)).collect(),
+    }
+}
+
+/// The issue with its whole descendant hierarchy, loaded with a single `$graphLookup`.
+#[get("/api/issues/{id}/tree")]
+pub async fn issues_tree(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let issue = match access::load_issue_for_member(&data, &path.into_inner(), user_id).await {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let descendants = match hierarchy::descendants(&data, &issue, None).await {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    let mut children_of: HashMap<ObjectId, Vec<IssueDb>> = HashMap::new();
+    for (child, _) in descendants {
+        if let Some(parent) = child.parent_issue_id {
+            children_of.entry(parent).or_default().push(child);
+        }
+    }
+
+    respond::ok_json(build(issue, &mut children_of))
+}
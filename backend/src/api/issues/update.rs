@@ -1,37 +1,18 @@
-use actix_web::{put, web, HttpRequest, Responder};
-use mongodb::bson::{doc, oid::ObjectId};
+use actix_web::{put, web, HttpRequest, HttpResponse, Responder};
+use mongodb::bson::{self, doc, oid::ObjectId, Bson, DateTime, Document};
 use mongodb::options::ReturnDocument;
 
+use crate::api::issues::fields::{self, validate_issue};
+use crate::api::issues::numbering;
 use crate::api::respond;
 use crate::auth;
-use crate::models::{IssueIn, IssueOut};
+use crate::flow;
+use crate::history;
+use crate::models::{issue_key, IssueIn, IssueOut, IssuePatchIn};
+use crate::rank;
+use crate::relations;
 use crate::server::AppState;
-
-fn normalize_status(status: &str) -> Option<&'static str> {
-    match status {
-        "todo" => Some("todo"),
-        "in_progress" => Some("in_progress"),
-        "in_review" => Some("in_review"),
-        // legacy alias
-        "backlog" => Some("in_review"),
-        "done" => Some("done"),
-        _ => None,
-    }
-}
-
-fn validate_issue(body: &IssueIn) -> Result<(), &'static str> {
-    if body.organization_id.trim().is_empty()
-        || body.title.trim().is_empty()
-        || body.description.trim().is_empty()
-        || body.status.trim().is_empty()
-    {
-        return Err("organizationId, title, description, status are required");
-    }
-    if normalize_status(body.status.trim()).is_none() {
-        return Err("Invalid status");
-    }
-    Ok(())
-}
+use crate::workflow;
 
 #[put("/api/issues/{id}")]
 pub async fn issues_update(
@@ -40,12 +21,12 @@ pub async fn issues_update(
     path: web::Path<String>,
     body: web::Json<IssueIn>,
 ) -> impl Responder {
-    let user_id = match auth::require_user_id(&req, &data.jwt_secret) {
+    let user_id = match auth::require_user_id(&req, &data).await {
         Ok(u) => u,
         Err(e) => return e,
     };
 
-    let issue_id = match ObjectId::parse_str(&path.into_inner()) {
+    let issue_id = match ObjectId::parse_str(path.into_inner()) {
         Ok(v) => v,
         Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid id"),
     };
@@ -55,12 +36,49 @@ pub async fn issues_update(
         return respond::error(actix_web::http::StatusCode::BAD_REQUEST, msg);
     }
 
-    let org_id = match ObjectId::parse_str(&body.organization_id) {
-        Ok(v) => v,
-        Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid organizationId"),
+    apply_changes(&data, user_id, issue_id, IssuePatchIn::from(body)).await
+}
+
+/// Validates and applies a change set to an issue (shared by `PUT` and `PATCH`).
+///
+/// Only fields present in `changes` (plus derived ones such as `key` when the
+/// issue moves to another organization) are written, using `$set`.
+pub async fn apply_changes(
+    data: &AppState,
+    user_id: ObjectId,
+    issue_id: ObjectId,
+    changes: IssuePatchIn,
+) -> HttpResponse {
+    let existing = match data.issues.find_one(doc! { "_id": issue_id }).await {
+        Ok(Some(v)) => v,
+        Ok(None) => return respond::error(actix_web::http::StatusCode::NOT_FOUND, "Issue not found"),
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    // membership check against the issue's current organization
+    let member = match data
+        .organizations
+        .find_one(doc! { "_id": existing.organization_id, "memberIds": user_id })
+        .await
+    {
+        Ok(Some(_)) => true,
+        Ok(None) => false,
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
     };
+    if !member {
+        return respond::error(actix_web::http::StatusCode::FORBIDDEN, "Not a member of this organization");
+    }
 
-    // membership check (and load org for memberIds validation)
+    let org_id = match changes.organization_id.as_deref() {
+        Some(s) => match ObjectId::parse_str(s.trim()) {
+            Ok(v) => v,
+            Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid organizationId"),
+        },
+        None => existing.organization_id,
+    };
+    let moved = org_id != existing.organization_id;
+
+    // membership check on the target organization (and load org for memberIds validation)
     let org = match data
         .organizations
         .find_one(doc! { "_id": org_id, "memberIds": user_id })
@@ -71,66 +89,231 @@ pub async fn issues_update(
         Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
     };
 
-    let assignee_oid = match body.assignee_id {
-        Some(s) => {
-            let s = s.trim();
-            if s.is_empty() {
-                None
-            } else {
-                let oid = match ObjectId::parse_str(s) {
+    // sub-issues would be left behind in the old organization, pointing at a parent outside it
+    if moved {
+        match data.issues.count_documents(doc! { "parentIssueId": issue_id }).await {
+            Ok(0) => {}
+            Ok(n) => {
+                return respond::error(
+                    actix_web::http::StatusCode::BAD_REQUEST,
+                    &format!("Issue has {n} sub-issue(s); move or detach them before moving it to another organization"),
+                )
+            }
+            Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+        }
+    }
+
+    let mut set = doc! {};
+    if changes.organization_id.is_some() {
+        set.insert("organizationId", org_id);
+    }
+
+    if let Some(title) = changes.title {
+        if title.trim().is_empty() {
+            return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "title cannot be empty");
+        }
+        set.insert("title", title);
+    }
+    if let Some(description) = changes.description {
+        if description.trim().is_empty() {
+            return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "description cannot be empty");
+        }
+        set.insert("description", description);
+    }
+
+    // status must exist in the (target) organization's workflow
+    let org_workflow = workflow::effective(&org.workflow);
+    if changes.status.is_some() || moved {
+        let wanted = changes.status.as_deref().map(str::trim).unwrap_or(&existing.status);
+        let status = match workflow::resolve_status(&org_workflow, wanted) {
+            Some(v) => v.key.clone(),
+            None => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid status"),
+        };
+        if !workflow::can_transition(&org_workflow, &existing.status, &status) {
+            return respond::error(
+                actix_web::http::StatusCode::BAD_REQUEST,
+                &format!("Status transition from {} to {} is not allowed", existing.status, status),
+            );
+        }
+        // only real status changes move `completedAt`, so re-saving an old done issue doesn't date it
+        if status != existing.status || moved {
+            let completed = workflow::is_completed(&org_workflow, &status);
+            if completed && !moved {
+                let blockers = match relations::open_blockers(data, &existing, &org_workflow).await {
                     Ok(v) => v,
-                    Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid assigneeId"),
+                    Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
                 };
-                if !org.member_ids.contains(&oid) {
-                    return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "assigneeId must be a member of the organization");
+                if !blockers.is_empty() {
+                    let keys: Vec<&str> = blockers.iter().map(|i| i.key.as_str()).collect();
+                    return respond::error(
+                        actix_web::http::StatusCode::CONFLICT,
+                        &format!("Blocked by open issues: {}", keys.join(", ")),
+                    );
                 }
-                Some(oid)
+            }
+            if completed && existing.completed_at.is_none() {
+                set.insert("completedAt", DateTime::now());
+            } else if !completed && existing.completed_at.is_some() {
+                set.insert("completedAt", Bson::Null);
             }
         }
-        None => None,
-    };
+        set.insert("status", status);
+    }
 
-    let status = match normalize_status(body.status.trim()) {
-        Some(v) => v.to_string(),
-        None => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid status"),
-    };
+    match changes.assignee_id {
+        Some(assignee) => {
+            let assignee_oid = match assignee.as_deref().map(str::trim) {
+                None | Some("") => None,
+                Some(s) => match ObjectId::parse_str(s) {
+                    Ok(v) => Some(v),
+                    Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid assigneeId"),
+                },
+            };
+            if let Some(oid) = assignee_oid
+                && !org.member_ids.contains(&oid)
+            {
+                return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "assigneeId must be a member of the organization");
+            }
+            set.insert("assigneeId", assignee_oid);
+        }
+        None if moved => {
+            if let Some(oid) = existing.assignee_id
+                && !org.member_ids.contains(&oid)
+            {
+                return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "assigneeId must be a member of the organization");
+            }
+        }
+        None => {}
+    }
 
-    let parent_oid = match body.parent_issue_id {
-        Some(s) => match ObjectId::parse_str(&s) {
-            Ok(v) => Some(v),
-            Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid parentIssueId"),
+    let parent_oid = match changes.parent_issue_id {
+        Some(parent) => match parent.as_deref().map(str::trim) {
+            None | Some("") => Some(None),
+            Some(s) => match ObjectId::parse_str(s) {
+                Ok(v) => Some(Some(v)),
+                Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid parentIssueId"),
+            },
         },
+        // the current parent has to live in the new organization too
+        None if moved => Some(existing.parent_issue_id),
         None => None,
     };
+    if let Some(parent_oid) = parent_oid {
+        if let Some(pid) = parent_oid {
+            let parent = match data
+                .issues
+                .find_one(doc! { "_id": pid, "organizationId": org_id })
+                .await
+            {
+                Ok(v) => v,
+                Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+            };
+            if parent.is_none() {
+                return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "parentIssueId not found in organization");
+            }
+            // the tree only changes shape when the parent actually changes
+            if existing.parent_issue_id != Some(pid)
+                && let Err(e) = fields::validate_parent(data, Some(&existing), pid).await
+            {
+                return e;
+            }
+        }
+        set.insert("parentIssueId", parent_oid);
+    }
 
-    if let Some(pid) = parent_oid {
-        let parent = match data
-            .issues
-            .find_one(doc! { "_id": pid, "organizationId": org_id })
-            .await
-        {
+    if let Some(p) = changes.priority.as_deref() {
+        let priority = match fields::parse_priority(p) {
             Ok(v) => v,
-            Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+            Err(msg) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, msg),
         };
-        if parent.is_none() {
-            return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "parentIssueId not found in organization");
+        set.insert("priority", priority);
+    }
+
+    match changes.estimate {
+        Some(Some(e)) => {
+            if let Err(msg) = fields::validate_estimate(e) {
+                return respond::error(actix_web::http::StatusCode::BAD_REQUEST, msg);
+            }
+            set.insert("estimate", e);
+        }
+        Some(None) => {
+            set.insert("estimate", Bson::Null);
         }
+        None => {}
     }
 
-    let update = doc! {
-        "$set": {
-            "organizationId": org_id,
-            "title": body.title,
-            "description": body.description,
-            "status": status,
-            "assigneeId": assignee_oid,
-            "parentIssueId": parent_oid,
+    // dates are validated together, against the stored value of whichever one isn't sent
+    let mut start_date = existing.start_date;
+    let mut due_date = existing.due_date;
+    for (field, change, value) in [
+        ("startDate", &changes.start_date, &mut start_date),
+        ("dueDate", &changes.due_date, &mut due_date),
+    ] {
+        match change {
+            Some(Some(raw)) => {
+                let Some(d) = fields::parse_date(raw) else {
+                    return respond::error(
+                        actix_web::http::StatusCode::BAD_REQUEST,
+                        &format!("Invalid {field} (expected YYYY-MM-DD)"),
+                    );
+                };
+                *value = Some(d);
+                set.insert(field, d);
+            }
+            Some(None) => {
+                *value = None;
+                set.insert(field, Bson::Null);
+            }
+            None => {}
         }
-    };
+    }
+    if let Err(msg) = fields::validate_dates(start_date, due_date) {
+        return respond::error(actix_web::http::StatusCode::BAD_REQUEST, msg);
+    }
+
+    match changes.label_ids.as_deref() {
+        Some(ids) => {
+            let label_ids = match fields::resolve_label_ids(data, org_id, ids).await {
+                Ok(v) => v,
+                Err(e) => return e,
+            };
+            set.insert("labelIds", label_ids);
+        }
+        // labels belong to the old organization, so they can't follow the issue
+        None if moved => {
+            set.insert("labelIds", Vec::<ObjectId>::new());
+        }
+        None => {}
+    }
+
+    // Moving to another organization gives the issue a fresh number/key there.
+    if moved {
+        let number = match numbering::next_issue_number(data, org_id).await {
+            Ok(Some(v)) => v,
+            Ok(None) => return respond::error(actix_web::http::StatusCode::NOT_FOUND, "Organization not found"),
+            Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+        };
+        set.insert("number", number);
+        set.insert("key", issue_key(&org.key, number));
+        // ranks only order issues within one organization
+        match rank::last(data, org_id).await {
+            Ok(v) => set.insert("rank", v),
+            Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+        };
+    }
+
+    // a PUT resends every field; only real changes count as an update
+    let stored = bson::to_document(&existing).unwrap_or_default();
+    let mut set: Document = set.into_iter().filter(|(k, v)| stored.get(k) != Some(v)).collect();
+    if set.is_empty() {
+        return respond::ok_json(IssueOut::from(existing));
+    }
+    set.insert("updatedAt", DateTime::now());
+    set.insert("updatedBy", user_id);
 
     let updated = match data
         .issues
-        .find_one_and_update(doc! { "_id": issue_id }, update)
+        .find_one_and_update(doc! { "_id": issue_id }, doc! { "$set": set })
         .return_document(ReturnDocument::After)
         .await
     {
@@ -139,6 +322,19 @@ pub async fn issues_update(
         Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
     };
 
+    history::record(data, user_id, Some(&existing), Some(&updated)).await;
+    if moved {
+        flow::reassign(data, updated.id, updated.organization_id).await;
+        history::reassign(data, updated.id, updated.organization_id).await;
+        // comments follow the issue, so deleting either organization treats them like the issue itself
+        let _ = data
+            .comments
+            .update_many(doc! { "issueId": updated.id }, doc! { "$set": { "organizationId": updated.organization_id } })
+            .await;
+        // relations only link issues within one organization (best effort, like history)
+        let _ = relations::remove_for(data, &[updated.id]).await;
+    }
+    flow::record(data, &updated, Some(&existing.status)).await;
+
     respond::ok_json(IssueOut::from(updated))
 }
-
@@ -0,0 +1,68 @@
+use actix_web::{post, web, HttpRequest, Responder};
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+
+use crate::api::labels::fields::{name_taken, validate_label};
+use crate::api::respond;
+use crate::auth;
+use crate::models::{LabelDb, LabelIn, LabelOut};
+use crate::server::AppState;
+
+#[post("/api/labels")]
+pub async fn labels_create(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<LabelIn>,
+) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let body = body.into_inner();
+    if let Err(msg) = validate_label(&body.name, &body.color) {
+        return respond::error(actix_web::http::StatusCode::BAD_REQUEST, msg);
+    }
+
+    let org_id = match ObjectId::parse_str(&body.organization_id) {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid organizationId"),
+    };
+
+    // membership check
+    let member = match data
+        .organizations
+        .find_one(doc! { "_id": org_id, "memberIds": user_id })
+        .await
+    {
+        Ok(Some(_)) => true,
+        Ok(None) => false,
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+    if !member {
+        return respond::error(actix_web::http::StatusCode::FORBIDDEN, "Not a member of this organization");
+    }
+
+    match name_taken(&data, org_id, &body.name, None).await {
+        Ok(false) => {}
+        Ok(true) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Label name already exists"),
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    }
+
+    let now = DateTime::now();
+    let label = LabelDb {
+        id: ObjectId::new(),
+        organization_id: org_id,
+        name: body.name.trim().to_string(),
+        color: body.color.trim().to_lowercase(),
+        created_at: Some(now),
+        created_by: Some(user_id),
+        updated_at: Some(now),
+        updated_by: Some(user_id),
+    };
+
+    if data.labels.insert_one(&label).await.is_err() {
+        return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+    }
+
+    respond::created_json(LabelOut::from(label))
+}
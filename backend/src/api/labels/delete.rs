@@ -0,0 +1,88 @@
+use actix_web::{delete, web, HttpRequest, Responder};
+use futures_util::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+
+use crate::api::respond;
+use crate::auth;
+use crate::history;
+use crate::models::IssueDb;
+use crate::server::AppState;
+
+/// Delete a label and remove it from every issue that carries it.
+#[delete("/api/labels/{id}")]
+pub async fn labels_delete(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let label_id = match ObjectId::parse_str(path.into_inner()) {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid id"),
+    };
+
+    let label = match data.labels.find_one(doc! { "_id": label_id }).await {
+        Ok(Some(v)) => v,
+        Ok(None) => return respond::error(actix_web::http::StatusCode::NOT_FOUND, "Label not found"),
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    // membership check
+    let member = match data
+        .organizations
+        .find_one(doc! { "_id": label.organization_id, "memberIds": user_id })
+        .await
+    {
+        Ok(Some(_)) => true,
+        Ok(None) => false,
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+    if !member {
+        return respond::error(actix_web::http::StatusCode::FORBIDDEN, "Not a member of this organization");
+    }
+
+    // the issues as they were, for their history
+    let mut cursor = match data.issues.find(doc! { "labelIds": label_id }).await {
+        Ok(c) => c,
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+    let mut tagged: Vec<IssueDb> = Vec::new();
+    while let Some(issue) = match cursor.try_next().await {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    } {
+        tagged.push(issue);
+    }
+
+    // untag issues first, so a failure never leaves issues pointing at a missing label
+    let now = DateTime::now();
+    if data
+        .issues
+        .update_many(
+            doc! { "labelIds": label_id },
+            doc! { "$pull": { "labelIds": label_id }, "$set": { "updatedAt": now, "updatedBy": user_id } },
+        )
+        .await
+        .is_err()
+    {
+        return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+    }
+
+    for before in &tagged {
+        let mut after = before.clone();
+        after.label_ids.retain(|id| *id != label_id);
+        after.updated_at = Some(now);
+        after.updated_by = Some(user_id);
+        history::record(&data, user_id, Some(before), Some(&after)).await;
+    }
+
+    if data.labels.delete_one(doc! { "_id": label_id }).await.is_err() {
+        return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+    }
+
+    respond::ok_json(serde_json::json!({ "ok": true }))
+}
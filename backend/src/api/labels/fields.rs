@@ -0,0 +1,39 @@
+//! Label validation shared by the label create/update handlers.
+
+use futures_util::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId};
+
+use crate::server::AppState;
+
+pub fn validate_label(name: &str, color: &str) -> Result<(), &'static str> {
+    let name = name.trim();
+    if name.is_empty() || color.trim().is_empty() {
+        return Err("name and color are required");
+    }
+    if name.chars().count() > 32 {
+        return Err("name must be at most 32 characters");
+    }
+    let color = color.trim();
+    let hex = color.strip_prefix('#').unwrap_or("");
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("color must be a hex color like #5e6ad2");
+    }
+    Ok(())
+}
+
+/// Whether another label in the organization already uses `name` (case-insensitive).
+pub async fn name_taken(
+    data: &AppState,
+    org_id: ObjectId,
+    name: &str,
+    except: Option<ObjectId>,
+) -> mongodb::error::Result<bool> {
+    let wanted = name.trim().to_lowercase();
+    let mut cursor = data.labels.find(doc! { "organizationId": org_id }).await?;
+    while let Some(label) = cursor.try_next().await? {
+        if Some(label.id) != except && label.name.to_lowercase() == wanted {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
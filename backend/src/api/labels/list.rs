@@ -0,0 +1,62 @@
+use actix_web::{get, web, HttpRequest, Responder};
+use futures_util::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId};
+
+use crate::api::respond;
+use crate::auth;
+use crate::models::{LabelOut, ListLabelsQuery};
+use crate::server::AppState;
+
+#[get("/api/labels")]
+pub async fn labels_list(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<ListLabelsQuery>,
+) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let Some(org_id_str) = query.organization_id.clone() else {
+        return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "organizationId is required");
+    };
+    let org_id = match ObjectId::parse_str(&org_id_str) {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid organizationId"),
+    };
+
+    // membership check
+    let member = match data
+        .organizations
+        .find_one(doc! { "_id": org_id, "memberIds": user_id })
+        .await
+    {
+        Ok(Some(_)) => true,
+        Ok(None) => false,
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+    if !member {
+        return respond::error(actix_web::http::StatusCode::FORBIDDEN, "Not a member of this organization");
+    }
+
+    let mut cursor = match data
+        .labels
+        .find(doc! { "organizationId": org_id })
+        .sort(doc! { "name": 1 })
+        .await
+    {
+        Ok(c) => c,
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    let mut out: Vec<LabelOut> = Vec::new();
+    while let Some(label) = match cursor.try_next().await {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    } {
+        out.push(LabelOut::from(label));
+    }
+
+    respond::ok_json(out)
+}
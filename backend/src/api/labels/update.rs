@@ -0,0 +1,81 @@
+use actix_web::{put, web, HttpRequest, Responder};
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use mongodb::options::ReturnDocument;
+
+use crate::api::labels::fields::{name_taken, validate_label};
+use crate::api::respond;
+use crate::auth;
+use crate::models::{LabelOut, LabelUpdateIn};
+use crate::server::AppState;
+
+/// Rename / recolor a label (requires membership in its organization).
+#[put("/api/labels/{id}")]
+pub async fn labels_update(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    body: web::Json<LabelUpdateIn>,
+) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let label_id = match ObjectId::parse_str(path.into_inner()) {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid id"),
+    };
+
+    let body = body.into_inner();
+    if let Err(msg) = validate_label(&body.name, &body.color) {
+        return respond::error(actix_web::http::StatusCode::BAD_REQUEST, msg);
+    }
+
+    let label = match data.labels.find_one(doc! { "_id": label_id }).await {
+        Ok(Some(v)) => v,
+        Ok(None) => return respond::error(actix_web::http::StatusCode::NOT_FOUND, "Label not found"),
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    // membership check
+    let member = match data
+        .organizations
+        .find_one(doc! { "_id": label.organization_id, "memberIds": user_id })
+        .await
+    {
+        Ok(Some(_)) => true,
+        Ok(None) => false,
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+    if !member {
+        return respond::error(actix_web::http::StatusCode::FORBIDDEN, "Not a member of this organization");
+    }
+
+    match name_taken(&data, label.organization_id, &body.name, Some(label_id)).await {
+        Ok(false) => {}
+        Ok(true) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Label name already exists"),
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    }
+
+    let update = doc! {
+        "$set": {
+            "name": body.name.trim(),
+            "color": body.color.trim().to_lowercase(),
+            "updatedAt": DateTime::now(),
+            "updatedBy": user_id,
+        }
+    };
+
+    let updated = match data
+        .labels
+        .find_one_and_update(doc! { "_id": label_id }, update)
+        .return_document(ReturnDocument::After)
+        .await
+    {
+        Ok(Some(v)) => v,
+        Ok(None) => return respond::error(actix_web::http::StatusCode::NOT_FOUND, "Label not found"),
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    respond::ok_json(LabelOut::from(updated))
+}
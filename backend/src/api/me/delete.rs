@@ -8,12 +8,14 @@ use crate::server::AppState;
 
 /// Delete the current account.
 ///
-/// - Delete organizations owned by the user (and all issues inside them)
+/// - Delete organizations owned by the user (and all issues/history/comments/labels/views inside them)
+/// - Delete the user's saved views in other organizations
 /// - Remove the user from member lists of other organizations
+/// - End all of the user's sessions
 /// - Delete the user document
 #[delete("/api/me")]
 pub async fn me_delete(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
-    let user_id = match auth::require_user_id(&req, &data.jwt_secret) {
+    let user_id = match auth::require_user_id(&req, &data).await {
         Ok(u) => u,
         Err(e) => return e,
     };
@@ -29,26 +31,57 @@ pub async fn me_delete(data: web::Data<AppState>, req: HttpRequest) -> impl Resp
         Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
     } {
         // delete issues in owned org
-        if let Err(_) = data.issues.delete_many(doc! { "organizationId": org.id }).await {
+        if data.issues.delete_many(doc! { "organizationId": org.id }).await.is_err() {
+            return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+        }
+        // delete history, status transitions, relations and comments in owned org
+        if data.issue_events.delete_many(doc! { "organizationId": org.id }).await.is_err() {
+            return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+        }
+        if data.status_transitions.delete_many(doc! { "organizationId": org.id }).await.is_err() {
+            return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+        }
+        if data.issue_relations.delete_many(doc! { "organizationId": org.id }).await.is_err() {
+            return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+        }
+        if data.comments.delete_many(doc! { "organizationId": org.id }).await.is_err() {
+            return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+        }
+        // delete labels and views in owned org
+        if data.labels.delete_many(doc! { "organizationId": org.id }).await.is_err() {
+            return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+        }
+        if data.views.delete_many(doc! { "organizationId": org.id }).await.is_err() {
             return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
         }
         // delete the org
-        if let Err(_) = data.organizations.delete_one(doc! { "_id": org.id }).await {
+        if data.organizations.delete_one(doc! { "_id": org.id }).await.is_err() {
             return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
         }
     }
 
+    // Delete the user's own views in other orgs
+    if data.views.delete_many(doc! { "ownerId": user_id }).await.is_err() {
+        return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+    }
+
     // Remove membership from other orgs
-    if let Err(_) = data
+    if data
         .organizations
         .update_many(doc! {}, doc! { "$pull": { "memberIds": user_id } })
         .await
+        .is_err()
     {
         return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
     }
 
+    // End every session of the user
+    if data.sessions.delete_many(doc! { "userId": user_id }).await.is_err() {
+        return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+    }
+
     // Delete user
-    if let Err(_) = data.users.delete_one(doc! { "_id": user_id }).await {
+    if data.users.delete_one(doc! { "_id": user_id }).await.is_err() {
         return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
     }
 
@@ -9,7 +9,7 @@ use crate::server::AppState;
 /// Profile overview for the currently logged-in user.
 #[get("/api/me")]
 pub async fn me_get(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
-    let user_id = match auth::require_user_id(&req, &data.jwt_secret) {
+    let user_id = match auth::require_user_id(&req, &data).await {
         Ok(u) => u,
         Err(e) => return e,
     };
@@ -1,3 +1,6 @@
 pub mod delete;
 pub mod get;
-
+pub mod password_update;
+pub mod sessions_delete;
+pub mod sessions_delete_all;
+pub mod sessions_list;
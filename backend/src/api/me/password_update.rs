@@ -0,0 +1,61 @@
+use actix_web::{put, web, HttpRequest, Responder};
+use mongodb::bson::doc;
+
+use crate::api::auth::tokens::sign_in;
+use crate::api::respond;
+use crate::auth;
+use crate::models::PasswordChangeIn;
+use crate::server::AppState;
+
+/// Change the current user's password.
+///
+/// Every existing session is ended (a stolen token must not outlive the old
+/// password); the response carries a fresh session for the caller.
+#[put("/api/me/password")]
+pub async fn me_password_update(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<PasswordChangeIn>,
+) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let body = body.into_inner();
+    if body.new_password.len() < 8 {
+        return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Password must be at least 8 characters");
+    }
+
+    let mut user = match data.users.find_one(doc! { "_id": user_id }).await {
+        Ok(Some(u)) => u,
+        Ok(None) => return respond::error(actix_web::http::StatusCode::NOT_FOUND, "User not found"),
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    if !bcrypt::verify(&body.current_password, &user.password_hash).unwrap_or(false) {
+        return respond::error(actix_web::http::StatusCode::FORBIDDEN, "Current password is incorrect");
+    }
+
+    user.password_hash = match bcrypt::hash(&body.new_password, bcrypt::DEFAULT_COST) {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Password hashing failed"),
+    };
+    if data
+        .users
+        .update_one(doc! { "_id": user_id }, doc! { "$set": { "password_hash": &user.password_hash } })
+        .await
+        .is_err()
+    {
+        return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+    }
+
+    if data.sessions.delete_many(doc! { "userId": user_id }).await.is_err() {
+        return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+    }
+
+    match sign_in(&data, &req, user).await {
+        Ok(out) => respond::ok_json(out),
+        Err(e) => e,
+    }
+}
@@ -0,0 +1,34 @@
+use actix_web::{delete, web, HttpRequest, Responder};
+use mongodb::bson::{doc, oid::ObjectId};
+
+use crate::api::respond;
+use crate::auth;
+use crate::server::AppState;
+
+/// Sign out one session of the current user (e.g. a lost device).
+#[delete("/api/me/sessions/{id}")]
+pub async fn me_sessions_delete(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let session_id = match ObjectId::parse_str(path.into_inner()) {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid id"),
+    };
+
+    match data
+        .sessions
+        .delete_one(doc! { "_id": session_id, "userId": user_id })
+        .await
+    {
+        Ok(r) if r.deleted_count == 0 => respond::error(actix_web::http::StatusCode::NOT_FOUND, "Session not found"),
+        Ok(_) => respond::ok_json(serde_json::json!({ "ok": true })),
+        Err(_) => respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    }
+}
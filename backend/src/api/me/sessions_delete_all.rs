@@ -0,0 +1,20 @@
+use actix_web::{delete, web, HttpRequest, Responder};
+use mongodb::bson::doc;
+
+use crate::api::respond;
+use crate::auth;
+use crate::server::AppState;
+
+/// Sign out everywhere: ends every session of the current user, this one included.
+#[delete("/api/me/sessions")]
+pub async fn me_sessions_delete_all(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    match data.sessions.delete_many(doc! { "userId": user_id }).await {
+        Ok(r) => respond::ok_json(serde_json::json!({ "ok": true, "ended": r.deleted_count })),
+        Err(_) => respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    }
+}
@@ -0,0 +1,38 @@
+use actix_web::{get, web, HttpRequest, Responder};
+use futures_util::TryStreamExt;
+use mongodb::bson::{doc, DateTime};
+
+use crate::api::respond;
+use crate::auth;
+use crate::models::SessionOut;
+use crate::server::AppState;
+use crate::sessions;
+
+/// Active logins of the current user, most recently used first.
+#[get("/api/me/sessions")]
+pub async fn me_sessions_list(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    let (user_id, session_id) = match auth::require_session(&req, &data).await {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let mut cursor = match data
+        .sessions
+        .find(doc! { "userId": user_id, "expiresAt": { "$gt": DateTime::now() } })
+        .sort(doc! { "lastSeenAt": -1, "_id": -1 })
+        .await
+    {
+        Ok(c) => c,
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    let mut out: Vec<SessionOut> = Vec::new();
+    while let Some(s) = match cursor.try_next().await {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    } {
+        out.push(sessions::to_out(s, session_id));
+    }
+
+    respond::ok_json(out)
+}
@@ -5,3 +5,8 @@ pub mod organizations;
 pub mod issues;
 pub mod me;
 
+pub mod labels;
+pub mod comments;
+pub mod views;
+pub mod search;
+pub mod relations;
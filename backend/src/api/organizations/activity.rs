@@ -0,0 +1,75 @@
+use actix_web::{get, web, HttpRequest, Responder};
+use futures_util::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId};
+
+use crate::api::respond;
+use crate::auth;
+use crate::models::{ActivityQuery, IssueEventOut};
+use crate::server::AppState;
+
+/// Organization-wide activity feed (issue history events), newest first.
+///
+/// Page with `?before=<last event id>`; `limit` defaults to 50 (max 200).
+#[get("/api/organizations/{id}/activity")]
+pub async fn organizations_activity(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<ActivityQuery>,
+) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let org_id = match ObjectId::parse_str(path.into_inner()) {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid id"),
+    };
+
+    // membership check
+    let member = match data
+        .organizations
+        .find_one(doc! { "_id": org_id, "memberIds": user_id })
+        .await
+    {
+        Ok(Some(_)) => true,
+        Ok(None) => false,
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+    if !member {
+        return respond::error(actix_web::http::StatusCode::FORBIDDEN, "Not a member of this organization");
+    }
+
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+
+    let mut filter = doc! { "organizationId": org_id };
+    if let Some(before) = query.before.clone() {
+        let before = match ObjectId::parse_str(&before) {
+            Ok(v) => v,
+            Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid before"),
+        };
+        filter.insert("_id", doc! { "$lt": before });
+    }
+
+    let mut cursor = match data
+        .issue_events
+        .find(filter)
+        .sort(doc! { "_id": -1 })
+        .limit(limit)
+        .await
+    {
+        Ok(c) => c,
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    let mut out: Vec<IssueEventOut> = Vec::new();
+    while let Some(event) = match cursor.try_next().await {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    } {
+        out.push(IssueEventOut::from(event));
+    }
+
+    respond::ok_json(out)
+}
@@ -1,9 +1,9 @@
 use actix_web::{post, web, HttpRequest, Responder};
-use mongodb::bson::{doc, oid::ObjectId};
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
 
 use crate::api::respond;
 use crate::auth;
-use crate::models::{OrganizationAddMemberIn, OrganizationOut};
+use crate::models::{email_collation, normalize_email, OrganizationAddMemberIn, OrganizationOut};
 use crate::server::AppState;
 
 /// Add a user to an organization by email (owner-only).
@@ -14,17 +14,17 @@ pub async fn organizations_add_member(
     path: web::Path<String>,
     body: web::Json<OrganizationAddMemberIn>,
 ) -> impl Responder {
-    let user_id = match auth::require_user_id(&req, &data.jwt_secret) {
+    let user_id = match auth::require_user_id(&req, &data).await {
         Ok(u) => u,
         Err(e) => return e,
     };
 
-    let org_id = match ObjectId::parse_str(&path.into_inner()) {
+    let org_id = match ObjectId::parse_str(path.into_inner()) {
         Ok(v) => v,
         Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid id"),
     };
 
-    let email = body.email.trim().to_lowercase();
+    let email = normalize_email(&body.email);
     if email.is_empty() || !email.contains('@') {
         return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid email");
     }
@@ -39,17 +39,32 @@ pub async fn organizations_add_member(
         return respond::error(actix_web::http::StatusCode::FORBIDDEN, "Only the owner can add members");
     }
 
-    let user = match data.users.find_one(doc! { "email": &email }).await {
+    let user = match data
+        .users
+        .find_one(doc! { "email": &email })
+        .collation(email_collation())
+        .await
+    {
         Ok(Some(u)) => u,
         Ok(None) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "User not found"),
         Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
     };
 
+    if org.require_verified_members && user.unverified {
+        return respond::error(
+            actix_web::http::StatusCode::BAD_REQUEST,
+            "This organization only accepts members with a verified email",
+        );
+    }
+
     let updated = match data
         .organizations
         .find_one_and_update(
             doc! { "_id": org_id },
-            doc! { "$addToSet": { "memberIds": user.id } },
+            doc! {
+                "$addToSet": { "memberIds": user.id },
+                "$set": { "updatedAt": DateTime::now(), "updatedBy": user_id },
+            },
         )
         .return_document(mongodb::options::ReturnDocument::After)
         .await
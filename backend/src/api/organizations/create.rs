@@ -1,5 +1,5 @@
 use actix_web::{post, web, HttpRequest, Responder};
-use mongodb::bson::{doc, oid::ObjectId};
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
 
 use crate::api::respond;
 use crate::auth;
@@ -23,7 +23,7 @@ pub async fn organizations_create(
     req: HttpRequest,
     body: web::Json<OrganizationCreateIn>,
 ) -> impl Responder {
-    let user_id = match auth::require_user_id(&req, &data.jwt_secret) {
+    let user_id = match auth::require_user_id(&req, &data).await {
         Ok(u) => u,
         Err(e) => return e,
     };
@@ -46,15 +46,23 @@ pub async fn organizations_create(
         return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Organization key already exists");
     }
 
+    let now = DateTime::now();
     let org = OrganizationDb {
         id: ObjectId::new(),
         name: body.name,
         key: body.key.to_uppercase(),
         owner_id: user_id,
         member_ids: vec![user_id],
+        issue_counter: 0,
+        workflow: Vec::new(),
+        require_verified_members: body.require_verified_members.unwrap_or(false),
+        created_at: Some(now),
+        created_by: Some(user_id),
+        updated_at: Some(now),
+        updated_by: Some(user_id),
     };
 
-    if let Err(_) = data.organizations.insert_one(&org).await {
+    if data.organizations.insert_one(&org).await.is_err() {
         return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
     }
 
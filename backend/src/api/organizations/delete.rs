@@ -6,19 +6,19 @@ use crate::auth;
 use crate::server::AppState;
 
 /// Delete an organization (owner-only).
-/// Also deletes all issues that belong to this organization.
+/// Also deletes all issues, history, comments, labels and saved views that belong to this organization.
 #[delete("/api/organizations/{id}")]
 pub async fn organizations_delete(
     data: web::Data<AppState>,
     req: HttpRequest,
     id: web::Path<String>,
 ) -> impl Responder {
-    let user_id = match auth::require_user_id(&req, &data.jwt_secret) {
+    let user_id = match auth::require_user_id(&req, &data).await {
         Ok(u) => u,
         Err(e) => return e,
     };
 
-    let org_id = match ObjectId::parse_str(&id.into_inner()) {
+    let org_id = match ObjectId::parse_str(id.into_inner()) {
         Ok(v) => v,
         Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid id"),
     };
@@ -34,11 +34,35 @@ pub async fn organizations_delete(
     }
 
     // delete issues first (best effort)
-    if let Err(_) = data.issues.delete_many(doc! { "organizationId": org_id }).await {
+    if data.issues.delete_many(doc! { "organizationId": org_id }).await.is_err() {
         return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
     }
 
-    if let Err(_) = data.organizations.delete_one(doc! { "_id": org_id }).await {
+    if data.issue_events.delete_many(doc! { "organizationId": org_id }).await.is_err() {
+        return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+    }
+
+    if data.comments.delete_many(doc! { "organizationId": org_id }).await.is_err() {
+        return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+    }
+
+    if data.status_transitions.delete_many(doc! { "organizationId": org_id }).await.is_err() {
+        return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+    }
+
+    if data.issue_relations.delete_many(doc! { "organizationId": org_id }).await.is_err() {
+        return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+    }
+
+    if data.labels.delete_many(doc! { "organizationId": org_id }).await.is_err() {
+        return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+    }
+
+    if data.views.delete_many(doc! { "organizationId": org_id }).await.is_err() {
+        return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+    }
+
+    if data.organizations.delete_one(doc! { "_id": org_id }).await.is_err() {
         return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
     }
 
@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+
+use actix_web::{get, web, HttpRequest, Responder};
+use futures_util::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+
+use crate::api::issues::access::load_issue_for_member;
+use crate::api::respond;
+use crate::auth;
+use crate::flow::{self, DAY_MS};
+use crate::hierarchy;
+use crate::models::{parse_time, FlowQuery, IssueDb, StatusTransitionDb};
+use crate::server::AppState;
+use crate::workflow;
+
+const DEFAULT_DAYS: i64 = 30;
+const MAX_DAYS: i64 = 366;
+
+/// Cumulative flow / burndown: issue counts per status at the end of each day.
+/// With `parentIssueId`, the scope is that issue and all of its sub-issues.
+///
+/// Only issues that still exist are read, so deleted issues are missing from
+/// every day, including the days before they were deleted. Past days are a
+/// reconstruction of the current issues, not an exact history.
+#[get("/api/organizations/{id}/flow")]
+pub async fn organizations_flow(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<FlowQuery>,
+) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let org_id = match ObjectId::parse_str(path.into_inner()) {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid id"),
+    };
+
+    // membership check (and load org for its workflow)
+    let org = match data
+        .organizations
+        .find_one(doc! { "_id": org_id, "memberIds": user_id })
+        .await
+    {
+        Ok(Some(v)) => v,
+        Ok(None) => return respond::error(actix_web::http::StatusCode::FORBIDDEN, "Not a member of this organization"),
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    // whole UTC days
+    let day_start = |dt: DateTime| dt.timestamp_millis() - dt.timestamp_millis().rem_euclid(DAY_MS);
+    let last_day = match query.to.as_deref() {
+        Some(raw) => match parse_time(raw) {
+            Some(v) => day_start(v),
+            None => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid to"),
+        },
+        None => day_start(DateTime::now()),
+    };
+    // the future looks like today
+    let last_day = last_day.min(day_start(DateTime::now()));
+    let first_day = match query.from.as_deref() {
+        Some(raw) => match parse_time(raw) {
+            Some(v) => day_start(v),
+            None => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid from"),
+        },
+        None => last_day - (DEFAULT_DAYS - 1) * DAY_MS,
+    };
+    if first_day > last_day {
+        return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "from must not be after to");
+    }
+    let days = (last_day - first_day) / DAY_MS + 1;
+    if days > MAX_DAYS {
+        return respond::error(
+            actix_web::http::StatusCode::BAD_REQUEST,
+            &format!("At most {MAX_DAYS} days can be requested"),
+        );
+    }
+    let range_end = DateTime::from_millis(last_day + DAY_MS);
+
+    // issues in scope: the whole organization, or a parent's subtree
+    let issues: Vec<IssueDb> = match query.parent_issue_id.as_deref() {
+        Some(parent_id) => {
+            let parent = match load_issue_for_member(&data, parent_id, user_id).await {
+                Ok(v) => v,
+                Err(e) => return e,
+            };
+            if parent.organization_id != org_id {
+                return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "parentIssueId belongs to another organization");
+            }
+            let descendants = match hierarchy::descendants(&data, &parent, None).await {
+                Ok(v) => v,
+                Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+            };
+            let mut scoped = vec![parent];
+            scoped.extend(descendants.into_iter().map(|(issue, _)| issue));
+            scoped
+        }
+        None => {
+            let mut cursor = match data.issues.find(doc! { "organizationId": org_id }).await {
+                Ok(c) => c,
+                Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+            };
+            let mut out = Vec::new();
+            while let Some(issue) = match cursor.try_next().await {
+                Ok(v) => v,
+                Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+            } {
+                out.push(issue);
+            }
+            out
+        }
+    };
+    let issues: Vec<IssueDb> = issues.into_iter().filter(|i| i.created_time() < range_end).collect();
+
+    // all transitions, even later ones: the first one's `from` is the status before it
+    let issue_ids: Vec<ObjectId> = issues.iter().map(|i| i.id).collect();
+    let mut cursor = match data
+        .status_transitions
+        .find(doc! { "issueId": { "$in": &issue_ids } })
+        .await
+    {
+        Ok(c) => c,
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+    let mut transitions: HashMap<ObjectId, Vec<StatusTransitionDb>> = HashMap::new();
+    while let Some(t) = match cursor.try_next().await {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    } {
+        transitions.entry(t.issue_id).or_default().push(t);
+    }
+
+    let org_workflow = workflow::effective(&org.workflow);
+    respond::ok_json(flow::daily(
+        &issues,
+        transitions,
+        &org_workflow,
+        DateTime::from_millis(first_day),
+        days as usize,
+    ))
+}
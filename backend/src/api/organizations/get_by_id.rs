@@ -12,12 +12,12 @@ pub async fn organizations_get_by_id(
     req: HttpRequest,
     id: web::Path<String>,
 ) -> impl Responder {
-    let user_id = match auth::require_user_id(&req, &data.jwt_secret) {
+    let user_id = match auth::require_user_id(&req, &data).await {
         Ok(u) => u,
         Err(e) => return e,
     };
 
-    let oid = match ObjectId::parse_str(&id.into_inner()) {
+    let oid = match ObjectId::parse_str(id.into_inner()) {
         Ok(v) => v,
         Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid id"),
     };
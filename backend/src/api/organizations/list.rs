@@ -12,7 +12,7 @@ pub async fn organizations_list(
     data: web::Data<AppState>,
     req: HttpRequest,
 ) -> impl Responder {
-    let user_id = match auth::require_user_id(&req, &data.jwt_secret) {
+    let user_id = match auth::require_user_id(&req, &data).await {
         Ok(u) => u,
         Err(e) => return e,
     };
@@ -15,12 +15,12 @@ pub async fn organizations_members_list(
     req: HttpRequest,
     path: web::Path<String>,
 ) -> impl Responder {
-    let user_id = match auth::require_user_id(&req, &data.jwt_secret) {
+    let user_id = match auth::require_user_id(&req, &data).await {
         Ok(u) => u,
         Err(e) => return e,
     };
 
-    let org_id = match ObjectId::parse_str(&path.into_inner()) {
+    let org_id = match ObjectId::parse_str(path.into_inner()) {
         Ok(v) => v,
         Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid id"),
     };
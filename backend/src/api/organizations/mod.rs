@@ -1,7 +1,13 @@
+pub mod activity;
 pub mod create;
 pub mod delete;
 pub mod add_member;
 pub mod get_by_id;
 pub mod list;
 pub mod members_list;
+pub mod patch;
 
+pub mod workflow_get;
+pub mod workflow_update;
+pub mod stats;
+pub mod flow;
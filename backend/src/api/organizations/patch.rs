@@ -0,0 +1,58 @@
+use actix_web::{patch, web, HttpRequest, Responder};
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use mongodb::options::ReturnDocument;
+
+use crate::api::respond;
+use crate::auth;
+use crate::models::{OrganizationOut, OrganizationPatchIn};
+use crate::server::AppState;
+
+/// Change organization settings (owner-only). Only the fields sent are changed.
+#[patch("/api/organizations/{id}")]
+pub async fn organizations_patch(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    body: web::Json<OrganizationPatchIn>,
+) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let org_id = match ObjectId::parse_str(path.into_inner()) {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid id"),
+    };
+
+    let org = match data.organizations.find_one(doc! { "_id": org_id }).await {
+        Ok(Some(v)) => v,
+        Ok(None) => return respond::error(actix_web::http::StatusCode::NOT_FOUND, "Organization not found"),
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    if org.owner_id != user_id {
+        return respond::error(actix_web::http::StatusCode::FORBIDDEN, "Only the owner can change settings");
+    }
+
+    let mut set = doc! {};
+    if let Some(v) = body.require_verified_members {
+        set.insert("requireVerifiedMembers", v);
+    }
+    if set.is_empty() {
+        return respond::ok_json(OrganizationOut::from(org));
+    }
+    set.insert("updatedAt", DateTime::now());
+    set.insert("updatedBy", user_id);
+
+    match data
+        .organizations
+        .find_one_and_update(doc! { "_id": org_id }, doc! { "$set": set })
+        .return_document(ReturnDocument::After)
+        .await
+    {
+        Ok(Some(v)) => respond::ok_json(OrganizationOut::from(v)),
+        Ok(None) => respond::error(actix_web::http::StatusCode::NOT_FOUND, "Organization not found"),
+        Err(_) => respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+
+use actix_web::{get, web, HttpRequest, Responder};
+use futures_util::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId, Bson, DateTime, Document};
+
+use crate::api::respond;
+use crate::auth;
+use crate::models::{
+    parse_time, rfc3339, AssigneeCountOut, OpenTotalOut, OrganizationStatsOut, ParentCountOut, StatsQuery,
+    StatsWindowOut, StatusCountOut,
+};
+use crate::server::AppState;
+use crate::workflow;
+
+const DEFAULT_WINDOW_DAYS: i64 = 30;
+/// Max parents listed in `openSubIssues`.
+const MAX_PARENTS: i64 = 50;
+
+fn count(d: &Document, key: &str) -> i64 {
+    match d.get(key) {
+        Some(Bson::Int32(v)) => *v as i64,
+        Some(Bson::Int64(v)) => *v,
+        _ => 0,
+    }
+}
+
+/// `{ $count }` facets come back as `[{ n }]`, or `[]` when nothing matched.
+fn facet_count(result: &Document, facet: &str) -> i64 {
+    result
+        .get_array(facet)
+        .ok()
+        .and_then(|a| a.first())
+        .and_then(Bson::as_document)
+        .map(|d| count(d, "n"))
+        .unwrap_or(0)
+}
+
+fn facet_docs<'a>(result: &'a Document, facet: &str) -> impl Iterator<Item = &'a Document> {
+    result
+        .get_array(facet)
+        .map(|a| a.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(Bson::as_document)
+}
+
+/// Dashboard statistics, computed in one aggregation over the organization's issues.
+#[get("/api/organizations/{id}/stats")]
+pub async fn organizations_stats(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<StatsQuery>,
+) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let org_id = match ObjectId::parse_str(path.into_inner()) {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid id"),
+    };
+
+    // membership check (and load org for its workflow)
+    let org = match data
+        .organizations
+        .find_one(doc! { "_id": org_id, "memberIds": user_id })
+        .await
+    {
+        Ok(Some(v)) => v,
+        Ok(None) => return respond::error(actix_web::http::StatusCode::FORBIDDEN, "Not a member of this organization"),
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    let until = match query.until.as_deref() {
+        Some(raw) => match parse_time(raw) {
+            Some(v) => v,
+            None => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid until"),
+        },
+        None => DateTime::now(),
+    };
+    let since = match query.since.as_deref() {
+        Some(raw) => match parse_time(raw) {
+            Some(v) => v,
+            None => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid since"),
+        },
+        None => DateTime::from_millis(until.timestamp_millis() - DEFAULT_WINDOW_DAYS * 24 * 60 * 60 * 1000),
+    };
+    if since >= until {
+        return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "since must be before until");
+    }
+
+    let org_workflow = workflow::effective(&org.workflow);
+    let is_open = doc! { "$not": [{ "$in": ["$status", workflow::closed_keys(&org_workflow)] }] };
+    let open_sum = doc! { "$sum": { "$cond": [is_open.clone(), 1, 0] } };
+
+    // issues written before `createdAt` was recorded fall back to their `_id` time
+    let created_at = doc! { "$ifNull": ["$createdAt", { "$toDate": "$_id" }] };
+
+    let pipeline = vec![
+        doc! { "$match": { "organizationId": org_id } },
+        doc! { "$facet": {
+            "total": [{ "$count": "n" }],
+            "open": [{ "$match": { "$expr": is_open } }, { "$count": "n" }],
+            "byStatus": [{ "$group": { "_id": "$status", "n": { "$sum": 1 } } }],
+            "byAssignee": [
+                { "$group": { "_id": "$assigneeId", "total": { "$sum": 1 }, "open": open_sum.clone() } },
+                { "$lookup": { "from": data.users.name(), "localField": "_id", "foreignField": "_id", "as": "user" } },
+                { "$project": { "total": 1, "open": 1, "name": { "$first": "$user.name" } } },
+                { "$sort": { "open": -1, "total": -1, "_id": 1 } },
+            ],
+            "subIssues": [
+                { "$match": { "parentIssueId": { "$ne": Bson::Null } } },
+                { "$group": { "_id": "$parentIssueId", "total": { "$sum": 1 }, "open": open_sum } },
+                { "$match": { "open": { "$gt": 0 } } },
+                { "$sort": { "open": -1, "_id": 1 } },
+                { "$limit": MAX_PARENTS },
+                { "$lookup": { "from": data.issues.name(), "localField": "_id", "foreignField": "_id", "as": "parent" } },
+                { "$project": {
+                    "total": 1,
+                    "open": 1,
+                    "key": { "$first": "$parent.key" },
+                    "title": { "$first": "$parent.title" },
+                } },
+            ],
+            "created": [
+                { "$match": { "$expr": { "$and": [
+                    { "$gte": [&created_at, since] },
+                    { "$lt": [&created_at, until] },
+                ] } } },
+                { "$count": "n" },
+            ],
+            "closed": [
+                { "$match": { "completedAt": { "$gte": since, "$lt": until } } },
+                { "$count": "n" },
+            ],
+        } },
+    ];
+
+    let result = match data.issues.aggregate(pipeline).await {
+        Ok(mut c) => match c.try_next().await {
+            Ok(v) => v.unwrap_or_default(),
+            Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+        },
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    // workflow order first (including empty columns), then statuses removed from the workflow
+    let mut status_counts: HashMap<String, i64> = facet_docs(&result, "byStatus")
+        .filter_map(|d| Some((d.get_str("_id").ok()?.to_string(), count(d, "n"))))
+        .collect();
+    let mut by_status: Vec<StatusCountOut> = org_workflow
+        .iter()
+        .map(|s| StatusCountOut {
+            status: s.key.clone(),
+            name: s.name.clone(),
+            category: Some(s.category.clone()),
+            count: status_counts.remove(&s.key).unwrap_or(0),
+        })
+        .collect();
+    let mut unknown: Vec<(String, i64)> = status_counts.into_iter().collect();
+    unknown.sort();
+    by_status.extend(unknown.into_iter().map(|(status, count)| StatusCountOut {
+        name: status.clone(),
+        status,
+        category: None,
+        count,
+    }));
+
+    let mut by_assignee: Vec<AssigneeCountOut> = Vec::new();
+    let mut unassigned = OpenTotalOut { open: 0, total: 0 };
+    for d in facet_docs(&result, "byAssignee") {
+        match d.get_object_id("_id") {
+            Ok(id) => by_assignee.push(AssigneeCountOut {
+                assignee_id: id.to_hex(),
+                name: d.get_str("name").ok().map(str::to_string),
+                open: count(d, "open"),
+                total: count(d, "total"),
+            }),
+            Err(_) => {
+                unassigned = OpenTotalOut {
+                    open: count(d, "open"),
+                    total: count(d, "total"),
+                }
+            }
+        }
+    }
+
+    let open_sub_issues: Vec<ParentCountOut> = facet_docs(&result, "subIssues")
+        .filter_map(|d| {
+            Some(ParentCountOut {
+                parent_issue_id: d.get_object_id("_id").ok()?.to_hex(),
+                key: d.get_str("key").ok().map(str::to_string),
+                title: d.get_str("title").ok().map(str::to_string),
+                open: count(d, "open"),
+                total: count(d, "total"),
+            })
+        })
+        .collect();
+
+    respond::ok_json(OrganizationStatsOut {
+        total: facet_count(&result, "total"),
+        open: facet_count(&result, "open"),
+        by_status,
+        by_assignee,
+        unassigned,
+        open_sub_issues,
+        window: StatsWindowOut {
+            since: rfc3339(since),
+            until: rfc3339(until),
+            created: facet_count(&result, "created"),
+            closed: facet_count(&result, "closed"),
+        },
+    })
+}
@@ -0,0 +1,37 @@
+use actix_web::{get, web, HttpRequest, Responder};
+use mongodb::bson::{doc, oid::ObjectId};
+
+use crate::api::respond;
+use crate::auth;
+use crate::server::AppState;
+use crate::workflow;
+
+/// Ordered workflow statuses of an organization (requires membership).
+#[get("/api/organizations/{id}/workflow")]
+pub async fn organizations_workflow_get(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let org_id = match ObjectId::parse_str(path.into_inner()) {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid id"),
+    };
+
+    let org = match data
+        .organizations
+        .find_one(doc! { "_id": org_id, "memberIds": user_id })
+        .await
+    {
+        Ok(Some(v)) => v,
+        Ok(None) => return respond::error(actix_web::http::StatusCode::FORBIDDEN, "Not a member of this organization"),
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    respond::ok_json(workflow::effective(&org.workflow))
+}
@@ -0,0 +1,86 @@
+use actix_web::{put, web, HttpRequest, Responder};
+use mongodb::bson::{doc, oid::ObjectId, to_bson, DateTime};
+
+use crate::api::respond;
+use crate::auth;
+use crate::models::WorkflowIn;
+use crate::server::AppState;
+use crate::workflow;
+
+/// Replace the workflow statuses of an organization (owner-only).
+///
+/// Statuses that are still used by issues cannot be removed; move those
+/// issues to another status first.
+#[put("/api/organizations/{id}/workflow")]
+pub async fn organizations_workflow_update(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    body: web::Json<WorkflowIn>,
+) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let org_id = match ObjectId::parse_str(path.into_inner()) {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid id"),
+    };
+
+    let mut statuses = body.into_inner().statuses;
+    for s in statuses.iter_mut() {
+        s.key = s.key.trim().to_string();
+        s.name = s.name.trim().to_string();
+        s.category = s.category.trim().to_string();
+    }
+    if let Err(msg) = workflow::validate(&statuses) {
+        return respond::error(actix_web::http::StatusCode::BAD_REQUEST, &msg);
+    }
+
+    let org = match data.organizations.find_one(doc! { "_id": org_id }).await {
+        Ok(Some(v)) => v,
+        Ok(None) => return respond::error(actix_web::http::StatusCode::NOT_FOUND, "Organization not found"),
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    if org.owner_id != user_id {
+        return respond::error(actix_web::http::StatusCode::FORBIDDEN, "Only the owner can change the workflow");
+    }
+
+    // refuse to orphan issues whose status would disappear
+    let keys: Vec<&str> = statuses.iter().map(|s| s.key.as_str()).collect();
+    let mut in_use: Vec<String> = match data
+        .issues
+        .distinct("status", doc! { "organizationId": org_id, "status": { "$nin": &keys } })
+        .await
+    {
+        Ok(v) => v.into_iter().filter_map(|b| b.as_str().map(str::to_string)).collect(),
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+    if !in_use.is_empty() {
+        in_use.sort();
+        return respond::error(
+            actix_web::http::StatusCode::BAD_REQUEST,
+            &format!("Statuses still used by issues: {}", in_use.join(", ")),
+        );
+    }
+
+    let workflow_bson = match to_bson(&statuses) {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Serialization error"),
+    };
+    if data
+        .organizations
+        .update_one(
+            doc! { "_id": org_id },
+            doc! { "$set": { "workflow": workflow_bson, "updatedAt": DateTime::now(), "updatedBy": user_id } },
+        )
+        .await
+        .is_err()
+    {
+        return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+    }
+
+    respond::ok_json(statuses)
+}
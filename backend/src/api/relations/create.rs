@@ -0,0 +1,88 @@
+use actix_web::{post, web, HttpRequest, Responder};
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+
+use crate::api::issues::access;
+use crate::api::respond;
+use crate::auth;
+use crate::models::{IssueRelationDb, IssueRelationIn};
+use crate::relations;
+use crate::server::AppState;
+
+/// Link two issues of the same organization, e.g. `{ "type": "blocked_by", "issueId": "..." }`.
+#[post("/api/issues/{id}/relations")]
+pub async fn relations_create(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    body: web::Json<IssueRelationIn>,
+) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let body = body.into_inner();
+    let Some((kind, swapped)) = relations::canonical(body.kind.trim()) else {
+        return respond::error(
+            actix_web::http::StatusCode::BAD_REQUEST,
+            &format!("type must be one of: {}", relations::TYPES.join(", ")),
+        );
+    };
+
+    let issue = match access::load_issue_for_member(&data, &path.into_inner(), user_id).await {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let other_id = match ObjectId::parse_str(body.issue_id.trim()) {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid issueId"),
+    };
+    if other_id == issue.id {
+        return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "An issue cannot be related to itself");
+    }
+    let other = match data
+        .issues
+        .find_one(doc! { "_id": other_id, "organizationId": issue.organization_id })
+        .await
+    {
+        Ok(Some(v)) => v,
+        Ok(None) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "issueId not found in this organization"),
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    let (source_id, target_id) = match (kind, swapped) {
+        ("relates_to", _) => (issue.id.min(other.id), issue.id.max(other.id)),
+        (_, false) => (issue.id, other.id),
+        (_, true) => (other.id, issue.id),
+    };
+
+    // one relation per pair and kind; two issues can't block each other
+    let mut conflicts = vec![doc! { "kind": kind, "sourceId": source_id, "targetId": target_id }];
+    if kind != "relates_to" {
+        conflicts.push(doc! { "kind": kind, "sourceId": target_id, "targetId": source_id });
+    }
+    match data.issue_relations.find_one(doc! { "$or": conflicts }).await {
+        Ok(None) => {}
+        Ok(Some(_)) => {
+            return respond::error(actix_web::http::StatusCode::CONFLICT, "These issues are already related this way");
+        }
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    }
+
+    let relation = IssueRelationDb {
+        id: ObjectId::new(),
+        organization_id: issue.organization_id,
+        kind: kind.to_string(),
+        source_id,
+        target_id,
+        created_by: user_id,
+        created_at: DateTime::now(),
+    };
+
+    if data.issue_relations.insert_one(&relation).await.is_err() {
+        return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+    }
+
+    respond::created_json(relations::to_out(&relation, issue.id, &other))
+}
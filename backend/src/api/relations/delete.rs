@@ -0,0 +1,48 @@
+use actix_web::{delete, web, HttpRequest, Responder};
+use mongodb::bson::{doc, oid::ObjectId};
+
+use crate::api::issues::access;
+use crate::api::respond;
+use crate::auth;
+use crate::server::AppState;
+
+/// Remove a relation; either of its two issues can be used in the path.
+#[delete("/api/issues/{id}/relations/{relation_id}")]
+pub async fn relations_delete(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let (issue_id, relation_id) = path.into_inner();
+    let relation_id = match ObjectId::parse_str(&relation_id) {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid relation id"),
+    };
+
+    let issue = match access::load_issue_for_member(&data, &issue_id, user_id).await {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let deleted = match data
+        .issue_relations
+        .delete_one(doc! {
+            "_id": relation_id,
+            "$or": [{ "sourceId": issue.id }, { "targetId": issue.id }],
+        })
+        .await
+    {
+        Ok(r) => r.deleted_count,
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+    if deleted == 0 {
+        return respond::error(actix_web::http::StatusCode::NOT_FOUND, "Relation not found");
+    }
+
+    respond::ok_json(serde_json::json!({ "ok": true }))
+}
@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+
+use actix_web::{get, web, HttpRequest, Responder};
+use futures_util::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId};
+
+use crate::api::issues::search::search_issues;
+use crate::api::respond;
+use crate::auth;
+use crate::models::{GlobalSearchHitOut, GlobalSearchQuery, OrganizationRefOut, PageOut, SearchHitOut};
+use crate::server::AppState;
+
+/// Searches issues of every organization the caller is a member of.
+/// Same ranking and cursors as `GET /api/issues/search`; each hit is tagged with its organization.
+#[get("/api/search")]
+pub async fn search_global(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<GlobalSearchQuery>,
+) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let q = query.q.clone().unwrap_or_default();
+    let q = q.trim().to_string();
+    if q.is_empty() {
+        return respond::ok_json(PageOut::<GlobalSearchHitOut> { items: Vec::new(), next_cursor: None });
+    }
+
+    // membership is resolved on every page, so leaving an org hides its issues right away
+    let mut cursor = match data.organizations.find(doc! { "memberIds": user_id }).await {
+        Ok(c) => c,
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+    let mut orgs: HashMap<ObjectId, OrganizationRefOut> = HashMap::new();
+    while let Some(org) = match cursor.try_next().await {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    } {
+        orgs.insert(
+            org.id,
+            OrganizationRefOut {
+                id: org.id.to_hex(),
+                name: org.name,
+                key: org.key,
+            },
+        );
+    }
+    if orgs.is_empty() {
+        return respond::ok_json(PageOut::<GlobalSearchHitOut> { items: Vec::new(), next_cursor: None });
+    }
+
+    let org_ids: Vec<ObjectId> = orgs.keys().copied().collect();
+    let scope = doc! { "organizationId": { "$in": org_ids } };
+    let page = match search_issues(&data, scope, &q, query.after.as_deref(), query.limit).await {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let items = page
+        .items
+        .into_iter()
+        .filter_map(|hit: SearchHitOut| {
+            let org_id = ObjectId::parse_str(&hit.issue.organization_id).ok()?;
+            let organization = orgs.get(&org_id)?.clone();
+            Some(GlobalSearchHitOut { hit, organization })
+        })
+        .collect();
+
+    respond::ok_json(PageOut { items, next_cursor: page.next_cursor })
+}
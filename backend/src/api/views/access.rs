@@ -0,0 +1,69 @@
+use actix_web::HttpResponse;
+use mongodb::bson::{doc, oid::ObjectId};
+
+use crate::api::issues::list::{compile_filter, default_order, sort_spec};
+use crate::api::respond;
+use crate::models::{OrganizationDb, ViewDb};
+use crate::server::AppState;
+
+/// Loads a view the user may see: their own views and shared views of
+/// organizations they are a member of. Also returns the organization.
+pub async fn load_view_for_user(
+    data: &AppState,
+    view_id: &str,
+    user_id: ObjectId,
+) -> Result<(ViewDb, OrganizationDb), HttpResponse> {
+    let oid = ObjectId::parse_str(view_id)
+        .map_err(|_| respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid id"))?;
+
+    let view = match data.views.find_one(doc! { "_id": oid }).await {
+        Ok(Some(v)) => v,
+        Ok(None) => return Err(respond::error(actix_web::http::StatusCode::NOT_FOUND, "View not found")),
+        Err(_) => return Err(respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error")),
+    };
+
+    // membership check (owners who left the org lose access to their views too)
+    let org = match data
+        .organizations
+        .find_one(doc! { "_id": view.organization_id, "memberIds": user_id })
+        .await
+    {
+        Ok(Some(v)) => v,
+        Ok(None) => return Err(respond::error(actix_web::http::StatusCode::FORBIDDEN, "Not a member of this organization")),
+        Err(_) => return Err(respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error")),
+    };
+
+    // private views look like they don't exist
+    if view.owner_id != user_id && !view.shared {
+        return Err(respond::error(actix_web::http::StatusCode::NOT_FOUND, "View not found"));
+    }
+
+    Ok((view, org))
+}
+
+/// Validates a view's name, filter and sort against its organization.
+/// Returns the normalized `(name, filter, sort, order)`.
+pub async fn validate_view(
+    data: &AppState,
+    user_id: ObjectId,
+    org: &OrganizationDb,
+    name: &str,
+    filter: Option<&str>,
+    sort: Option<&str>,
+    order: Option<&str>,
+) -> Result<(String, String, String, String), HttpResponse> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > 64 {
+        return Err(respond::error(actix_web::http::StatusCode::BAD_REQUEST, "name must be 1-64 characters"));
+    }
+
+    let filter = filter.unwrap_or_default().trim();
+    // compile once so unknown statuses/labels are rejected when saving, not when running
+    compile_filter(data, user_id, org, filter).await?;
+
+    let sort = sort.unwrap_or("created");
+    let order = order.unwrap_or(default_order(sort));
+    sort_spec(Some(sort), Some(order)).map_err(|msg| respond::error(actix_web::http::StatusCode::BAD_REQUEST, msg))?;
+
+    Ok((name.to_string(), filter.to_string(), sort.to_string(), order.to_string()))
+}
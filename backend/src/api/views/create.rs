@@ -0,0 +1,74 @@
+use actix_web::{post, web, HttpRequest, Responder};
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+
+use crate::api::respond;
+use crate::api::views::access::validate_view;
+use crate::auth;
+use crate::models::{ViewDb, ViewIn, ViewOut};
+use crate::server::AppState;
+
+#[post("/api/views")]
+pub async fn views_create(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<ViewIn>,
+) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let body = body.into_inner();
+    let org_id = match ObjectId::parse_str(&body.organization_id) {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid organizationId"),
+    };
+
+    // membership check (and load org to validate the filter against)
+    let org = match data
+        .organizations
+        .find_one(doc! { "_id": org_id, "memberIds": user_id })
+        .await
+    {
+        Ok(Some(v)) => v,
+        Ok(None) => return respond::error(actix_web::http::StatusCode::FORBIDDEN, "Not a member of this organization"),
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    let (name, filter, sort, order) = match validate_view(
+        &data,
+        user_id,
+        &org,
+        &body.name,
+        body.filter.as_deref(),
+        body.sort.as_deref(),
+        body.order.as_deref(),
+    )
+    .await
+    {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let now = DateTime::now();
+    let view = ViewDb {
+        id: ObjectId::new(),
+        organization_id: org_id,
+        owner_id: user_id,
+        name,
+        filter,
+        sort,
+        order,
+        shared: body.shared.unwrap_or(false),
+        created_at: Some(now),
+        created_by: Some(user_id),
+        updated_at: Some(now),
+        updated_by: Some(user_id),
+    };
+
+    if data.views.insert_one(&view).await.is_err() {
+        return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+    }
+
+    respond::created_json(ViewOut::from(view))
+}
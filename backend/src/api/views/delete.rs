@@ -0,0 +1,35 @@
+use actix_web::{delete, web, HttpRequest, Responder};
+use mongodb::bson::doc;
+
+use crate::api::respond;
+use crate::api::views::access::load_view_for_user;
+use crate::auth;
+use crate::server::AppState;
+
+#[delete("/api/views/{id}")]
+pub async fn views_delete(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let (view, org) = match load_view_for_user(&data, &path.into_inner(), user_id).await {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    // org owners may clean up shared views left behind by members
+    if view.owner_id != user_id && org.owner_id != user_id {
+        return respond::error(actix_web::http::StatusCode::FORBIDDEN, "Only the view owner can delete it");
+    }
+
+    if data.views.delete_one(doc! { "_id": view.id }).await.is_err() {
+        return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+    }
+
+    respond::ok_json(serde_json::json!({ "ok": true }))
+}
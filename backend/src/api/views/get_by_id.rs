@@ -0,0 +1,24 @@
+use actix_web::{get, web, HttpRequest, Responder};
+
+use crate::api::respond;
+use crate::api::views::access::load_view_for_user;
+use crate::auth;
+use crate::models::ViewOut;
+use crate::server::AppState;
+
+#[get("/api/views/{id}")]
+pub async fn views_get_by_id(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    match load_view_for_user(&data, &path.into_inner(), user_id).await {
+        Ok((view, _)) => respond::ok_json(ViewOut::from(view)),
+        Err(e) => e,
+    }
+}
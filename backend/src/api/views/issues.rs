@@ -0,0 +1,40 @@
+use actix_web::{get, web, HttpRequest, Responder};
+
+use crate::api::issues::list::list_issues;
+use crate::api::views::access::load_view_for_user;
+use crate::auth;
+use crate::models::{ListIssuesQuery, ViewIssuesQuery};
+use crate::server::AppState;
+
+/// Runs a saved view: same response shape as `GET /api/issues`.
+#[get("/api/views/{id}/issues")]
+pub async fn views_issues(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<ViewIssuesQuery>,
+) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let (view, org) = match load_view_for_user(&data, &path.into_inner(), user_id).await {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let query = query.into_inner();
+    let list_query = ListIssuesQuery {
+        organization_id: Some(org.id.to_hex()),
+        filter: Some(view.filter),
+        sort: Some(view.sort),
+        order: Some(view.order),
+        limit: query.limit,
+        after: query.after,
+        ..Default::default()
+    };
+
+    // "me" in the filter resolves to whoever runs the view
+    list_issues(&data, user_id, &org, &list_query).await
+}
@@ -0,0 +1,66 @@
+use actix_web::{get, web, HttpRequest, Responder};
+use futures_util::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId};
+
+use crate::api::respond;
+use crate::auth;
+use crate::models::{ListViewsQuery, ViewOut};
+use crate::server::AppState;
+
+#[get("/api/views")]
+pub async fn views_list(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<ListViewsQuery>,
+) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let Some(org_id_str) = query.organization_id.clone() else {
+        return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "organizationId is required");
+    };
+    let org_id = match ObjectId::parse_str(&org_id_str) {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::BAD_REQUEST, "Invalid organizationId"),
+    };
+
+    // membership check
+    let member = match data
+        .organizations
+        .find_one(doc! { "_id": org_id, "memberIds": user_id })
+        .await
+    {
+        Ok(Some(_)) => true,
+        Ok(None) => false,
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+    if !member {
+        return respond::error(actix_web::http::StatusCode::FORBIDDEN, "Not a member of this organization");
+    }
+
+    // own views plus everything shared with the organization
+    let mut cursor = match data
+        .views
+        .find(doc! {
+            "organizationId": org_id,
+            "$or": [ { "ownerId": user_id }, { "shared": true } ],
+        })
+        .sort(doc! { "name": 1 })
+        .await
+    {
+        Ok(c) => c,
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    let mut out: Vec<ViewOut> = Vec::new();
+    while let Some(view) = match cursor.try_next().await {
+        Ok(v) => v,
+        Err(_) => return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    } {
+        out.push(ViewOut::from(view));
+    }
+
+    respond::ok_json(out)
+}
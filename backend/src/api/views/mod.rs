@@ -0,0 +1,7 @@
+pub mod access;
+pub mod create;
+pub mod delete;
+pub mod get_by_id;
+pub mod issues;
+pub mod list;
+pub mod update;
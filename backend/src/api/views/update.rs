@@ -0,0 +1,63 @@
+use actix_web::{put, web, HttpRequest, Responder};
+use mongodb::bson::{doc, DateTime};
+
+use crate::api::respond;
+use crate::api::views::access::{load_view_for_user, validate_view};
+use crate::auth;
+use crate::models::{ViewOut, ViewUpdateIn};
+use crate::server::AppState;
+
+#[put("/api/views/{id}")]
+pub async fn views_update(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    body: web::Json<ViewUpdateIn>,
+) -> impl Responder {
+    let user_id = match auth::require_user_id(&req, &data).await {
+        Ok(u) => u,
+        Err(e) => return e,
+    };
+
+    let (mut view, org) = match load_view_for_user(&data, &path.into_inner(), user_id).await {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    // shared views are read-only for everyone but their owner
+    if view.owner_id != user_id {
+        return respond::error(actix_web::http::StatusCode::FORBIDDEN, "Only the view owner can edit it");
+    }
+
+    let body = body.into_inner();
+    let (name, filter, sort, order) = match validate_view(
+        &data,
+        user_id,
+        &org,
+        &body.name,
+        body.filter.as_deref(),
+        body.sort.as_deref(),
+        body.order.as_deref(),
+    )
+    .await
+    {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    view.name = name;
+    view.filter = filter;
+    view.sort = sort;
+    view.order = order;
+    if let Some(shared) = body.shared {
+        view.shared = shared;
+    }
+    view.updated_at = Some(DateTime::now());
+    view.updated_by = Some(user_id);
+
+    if data.views.replace_one(doc! { "_id": view.id }, &view).await.is_err() {
+        return respond::error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+    }
+
+    respond::ok_json(ViewOut::from(view))
+}
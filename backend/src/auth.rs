@@ -2,25 +2,71 @@ use actix_web::http::header;
 use actix_web::{HttpRequest, HttpResponse};
 use chrono::{Duration, Utc};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
-use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
 use serde::{Deserialize, Serialize};
 
+use crate::server::AppState;
+use crate::sessions;
 
-/// - Backend issues a JWT on login/signup
-/// - Frontend stores the token and sends `Authorization: Bearer <token>`
-/// - API endpoints verify the token and extract the user id (`sub`)
+/// Lifetime of an access token; clients renew it with their refresh token.
+pub const ACCESS_TOKEN_MINUTES: i64 = 15;
+/// Lifetime of an email verification link.
+pub const VERIFY_TOKEN_HOURS: i64 = 48;
+const VERIFY_PURPOSE: &str = "verify_email";
+
+/// - Backend issues a short-lived JWT plus a refresh token on login/signup (see `sessions.rs`)
+/// - Frontend stores both and sends `Authorization: Bearer <token>`
+/// - API endpoints verify the token, check its session still exists and extract the user id (`sub`)
 #[derive(Debug, Serialize, Deserialize)]
 struct Claims {
     // user id
     sub: String,
+    // session id
+    sid: String,
+    exp: usize,
+}
+
+/// Claims of an email verification link. `purpose` keeps these tokens and
+/// access tokens from being used in place of each other.
+#[derive(Debug, Serialize, Deserialize)]
+struct VerifyClaims {
+    sub: String,
+    email: String,
+    purpose: String,
     exp: usize,
 }
 
-/// Creates a signed JWT that expires in ~24h.
-pub fn issue_token(user_id: ObjectId, jwt_secret: &str) -> anyhow::Result<String> {
-    let exp = (Utc::now() + Duration::hours(24)).timestamp() as usize;
+/// Signs a link token confirming that `user_id` owns `email`.
+pub fn issue_verify_token(user_id: ObjectId, email: &str, jwt_secret: &str) -> anyhow::Result<String> {
+    let claims = VerifyClaims {
+        sub: user_id.to_hex(),
+        email: email.to_string(),
+        purpose: VERIFY_PURPOSE.to_string(),
+        exp: (Utc::now() + Duration::hours(VERIFY_TOKEN_HOURS)).timestamp() as usize,
+    };
+    Ok(encode(&Header::default(), &claims, &EncodingKey::from_secret(jwt_secret.as_bytes()))?)
+}
+
+/// Checks a verification token; returns the user id and email it confirms.
+pub fn decode_verify_token(token: &str, jwt_secret: &str) -> Option<(ObjectId, String)> {
+    let decoded = decode::<VerifyClaims>(
+        token.trim(),
+        &DecodingKey::from_secret(jwt_secret.as_bytes()),
+        &Validation::default(),
+    )
+    .ok()?;
+    if decoded.claims.purpose != VERIFY_PURPOSE {
+        return None;
+    }
+    Some((ObjectId::parse_str(&decoded.claims.sub).ok()?, decoded.claims.email))
+}
+
+/// Creates a signed JWT for a session that expires in `ACCESS_TOKEN_MINUTES`.
+pub fn issue_token(user_id: ObjectId, session_id: ObjectId, jwt_secret: &str) -> anyhow::Result<String> {
+    let exp = (Utc::now() + Duration::minutes(ACCESS_TOKEN_MINUTES)).timestamp() as usize;
     let claims = Claims {
         sub: user_id.to_hex(),
+        sid: session_id.to_hex(),
         exp,
     };
     Ok(encode(
@@ -30,10 +76,8 @@ pub fn issue_token(user_id: ObjectId, jwt_secret: &str) -> anyhow::Result<String
     )?)
 }
 
-/// Extracts user id from `Authorization
-/// Returns `HttpResponse::Unauthorized()` on failure so route handlers can
-/// `return e;` while still returning `impl Responder`.
-pub fn require_user_id(req: &HttpRequest, jwt_secret: &str) -> Result<ObjectId, HttpResponse> {
+/// Verifies the `Authorization` header and returns `(user id, session id)`.
+fn decode_claims(req: &HttpRequest, jwt_secret: &str) -> Result<(ObjectId, ObjectId), HttpResponse> {
     let header_value = req
         .headers()
         .get(header::AUTHORIZATION)
@@ -58,7 +102,35 @@ pub fn require_user_id(req: &HttpRequest, jwt_secret: &str) -> Result<ObjectId,
     )
     .map_err(|_| HttpResponse::Unauthorized().json(serde_json::json!({ "message": "Invalid token" })))?;
 
-    ObjectId::parse_str(&decoded.claims.sub)
-        .map_err(|_| HttpResponse::Unauthorized().json(serde_json::json!({ "message": "Invalid token subject" })))
+    let user_id = ObjectId::parse_str(&decoded.claims.sub)
+        .map_err(|_| HttpResponse::Unauthorized().json(serde_json::json!({ "message": "Invalid token subject" })))?;
+    let session_id = ObjectId::parse_str(&decoded.claims.sid)
+        .map_err(|_| HttpResponse::Unauthorized().json(serde_json::json!({ "message": "Invalid token session" })))?;
+    Ok((user_id, session_id))
 }
 
+/// Like `require_user_id`, but also returns the id of the session the token belongs to.
+pub async fn require_session(req: &HttpRequest, data: &AppState) -> Result<(ObjectId, ObjectId), HttpResponse> {
+    let (user_id, session_id) = decode_claims(req, &data.jwt_secret)?;
+
+    // logout deletes the session, which revokes its access tokens before they expire
+    match data
+        .sessions
+        .find_one(doc! { "_id": session_id, "userId": user_id, "expiresAt": { "$gt": DateTime::now() } })
+        .await
+    {
+        Ok(Some(session)) => {
+            sessions::touch(data, &session).await;
+            Ok((user_id, session_id))
+        }
+        Ok(None) => Err(HttpResponse::Unauthorized().json(serde_json::json!({ "message": "Session expired" }))),
+        Err(_) => Err(HttpResponse::InternalServerError().json(serde_json::json!({ "message": "Database error" }))),
+    }
+}
+
+/// Extracts user id from `Authorization` and checks its session is still active.
+/// Returns `HttpResponse::Unauthorized()` on failure so route handlers can
+/// `return e;` while still returning `impl Responder`.
+pub async fn require_user_id(req: &HttpRequest, data: &AppState) -> Result<ObjectId, HttpResponse> {
+    require_session(req, data).await.map(|(user_id, _)| user_id)
+}
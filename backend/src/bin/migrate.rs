@@ -0,0 +1,369 @@
+//! One-off data migrations; every step is safe to run again.
+//!
+//! Issue numbers: gives issues created before per-organization numbering a
+//! `number`/`key`, in `_id` order, drawing from the organization's `issueCounter`
+//! like new issues do.
+//!
+//! Audit fields: backfills `createdAt`/`createdBy`/`updatedAt`/`updatedBy` on
+//! documents written before the API recorded them.
+//!
+//! - `createdAt` comes from the `_id` timestamp
+//! - `createdBy` is the owner for organizations and views, and the author of the
+//!   issue's `created` history event for issues; labels have no known author
+//! - `updatedAt`/`updatedBy` come from an issue's latest history event, otherwise
+//!   they equal the created values
+//! - Only missing fields are written, so running it again is a no-op
+//!
+//! Completion dates: sets `completedAt` on issues already in a completed status
+//! before it was recorded, so closed counts over older windows include them.
+//!
+//! - The date is the latest status transition or history event into that status,
+//!   otherwise the issue's `updatedAt`
+//!
+//! User emails: lowercases and trims `users.email`, then creates the unique
+//! case-insensitive index on it.
+//!
+//! - Accounts whose emails differ only in case are listed and left untouched;
+//!   merge or rename them by hand, then run the migration again
+//! - The index can't be created while such duplicates exist
+//!
+//! Usage: `cargo run --bin migrate`
+
+use std::collections::HashMap;
+
+use futures_util::TryStreamExt;
+use mongodb::{
+    bson::{doc, oid::ObjectId, Bson, DateTime, Document},
+    options::{ClientOptions, Collation, CollationStrength, IndexOptions, ReturnDocument},
+    Client, Collection, IndexModel,
+};
+
+fn env_or(key: &str, default: &str) -> String {
+    std::env::var(key).unwrap_or_else(|_| default.to_string())
+}
+
+/// Numbers issues that have no `number` yet, oldest first within each organization.
+async fn number_issues(issues: &Collection<Document>, organizations: &Collection<Document>) -> anyhow::Result<()> {
+    let mut cursor = issues
+        .find(doc! { "$or": [{ "number": { "$exists": false } }, { "number": { "$lte": 0 } }] })
+        .projection(doc! { "_id": 1, "organizationId": 1 })
+        .sort(doc! { "_id": 1 })
+        .await?;
+    let mut pending: Vec<Document> = Vec::new();
+    while let Some(d) = cursor.try_next().await? {
+        pending.push(d);
+    }
+
+    let mut numbered = 0;
+    for d in &pending {
+        let (Ok(id), Ok(org_id)) = (d.get_object_id("_id"), d.get_object_id("organizationId")) else {
+            continue;
+        };
+        // same `$inc` as `numbering::next_issue_number`, so a running server can't hand out the number too
+        let Some(org) = organizations
+            .find_one_and_update(doc! { "_id": org_id }, doc! { "$inc": { "issueCounter": 1_i64 } })
+            .return_document(ReturnDocument::After)
+            .await?
+        else {
+            continue;
+        };
+        let number = match org.get("issueCounter") {
+            Some(Bson::Int64(n)) => *n,
+            Some(Bson::Int32(n)) => i64::from(*n),
+            _ => continue,
+        };
+        let key = format!("{}-{number}", org.get_str("key").unwrap_or_default());
+        issues
+            .update_one(doc! { "_id": id }, doc! { "$set": { "number": number, "key": key } })
+            .await?;
+        numbered += 1;
+    }
+    println!("issues: {numbered} numbered");
+    Ok(())
+}
+
+/// Sets `createdAt` from the `_id` time and `createdBy` from `owner_field`
+/// (if given), then copies both into missing `updatedAt`/`updatedBy`.
+/// Fields without a known value stay absent and read as `null`.
+async fn backfill(collection: &Collection<Document>, owner_field: Option<&str>) -> anyhow::Result<()> {
+    let created_at = collection
+        .update_many(
+            doc! { "createdAt": { "$exists": false } },
+            vec![doc! { "$set": { "createdAt": { "$toDate": "$_id" } } }],
+        )
+        .await?;
+
+    let created_by = match owner_field {
+        Some(field) => {
+            collection
+                .update_many(
+                    doc! { "createdBy": { "$exists": false } },
+                    vec![doc! { "$set": { "createdBy": format!("${field}") } }],
+                )
+                .await?
+                .modified_count
+        }
+        None => 0,
+    };
+
+    let updated = collection
+        .update_many(
+            doc! { "updatedAt": { "$exists": false } },
+            vec![doc! { "$set": { "updatedAt": "$createdAt", "updatedBy": "$createdBy" } }],
+        )
+        .await?;
+
+    println!(
+        "{}: createdAt {}, createdBy {}, updatedAt/updatedBy {}",
+        collection.name(),
+        created_at.modified_count,
+        created_by,
+        updated.modified_count,
+    );
+    Ok(())
+}
+
+struct IssueAudit {
+    created_by: Option<ObjectId>,
+    updated_at: DateTime,
+    updated_by: ObjectId,
+}
+
+/// Issue authorship from the change history, for issues still missing it.
+async fn backfill_issues(issues: &Collection<Document>, events: &Collection<Document>) -> anyhow::Result<()> {
+    let mut cursor = issues
+        .find(doc! { "$or": [{ "createdBy": { "$exists": false } }, { "updatedAt": { "$exists": false } }] })
+        .projection(doc! { "_id": 1, "createdBy": 1, "updatedAt": 1 })
+        .await?;
+    let mut pending: Vec<Document> = Vec::new();
+    while let Some(d) = cursor.try_next().await? {
+        pending.push(d);
+    }
+
+    let ids: Vec<ObjectId> = pending.iter().filter_map(|d| d.get_object_id("_id").ok()).collect();
+    let mut cursor = events
+        .aggregate(vec![
+            doc! { "$match": { "issueId": { "$in": &ids } } },
+            doc! { "$sort": { "at": 1, "_id": 1 } },
+            doc! { "$group": {
+                "_id": "$issueId",
+                "createdBy": { "$max": { "$cond": [{ "$eq": ["$kind", "created"] }, "$actorId", Bson::Null] } },
+                "updatedAt": { "$last": "$at" },
+                "updatedBy": { "$last": "$actorId" },
+            } },
+        ])
+        .await?;
+    let mut audits: HashMap<ObjectId, IssueAudit> = HashMap::new();
+    while let Some(d) = cursor.try_next().await? {
+        let (Ok(id), Ok(updated_at), Ok(updated_by)) =
+            (d.get_object_id("_id"), d.get_datetime("updatedAt"), d.get_object_id("updatedBy"))
+        else {
+            continue;
+        };
+        audits.insert(
+            id,
+            IssueAudit {
+                created_by: d.get_object_id("createdBy").ok(),
+                updated_at: *updated_at,
+                updated_by,
+            },
+        );
+    }
+
+    let mut from_history = 0;
+    for d in &pending {
+        let Ok(id) = d.get_object_id("_id") else {
+            continue;
+        };
+        let Some(audit) = audits.get(&id) else {
+            continue;
+        };
+        let mut set = doc! {};
+        if !d.contains_key("createdBy")
+            && let Some(created_by) = audit.created_by
+        {
+            set.insert("createdBy", created_by);
+        }
+        if !d.contains_key("updatedAt") {
+            set.insert("updatedAt", audit.updated_at);
+            set.insert("updatedBy", audit.updated_by);
+        }
+        if !set.is_empty() {
+            issues.update_one(doc! { "_id": id }, doc! { "$set": set }).await?;
+            from_history += 1;
+        }
+    }
+    println!("issues: {from_history} filled in from history");
+
+    // whatever history didn't cover falls back to the `_id` time
+    backfill(issues, None).await
+}
+
+/// Status keys in the `completed` category of an organization's workflow
+/// (`done` for organizations still on the default workflow).
+fn completed_keys(org: &Document) -> Vec<String> {
+    let statuses: Vec<&Document> = org
+        .get_array("workflow")
+        .map(|a| a.iter().filter_map(Bson::as_document).collect())
+        .unwrap_or_default();
+    if statuses.is_empty() {
+        return vec!["done".to_string()];
+    }
+    statuses
+        .into_iter()
+        .filter(|s| s.get_str("category") == Ok("completed"))
+        .filter_map(|s| s.get_str("key").ok().map(str::to_string))
+        .collect()
+}
+
+/// `at` of the newest document matching `filter`.
+async fn latest_at(collection: &Collection<Document>, filter: Document) -> anyhow::Result<Option<DateTime>> {
+    let found = collection
+        .find_one(filter)
+        .sort(doc! { "at": -1 })
+        .projection(doc! { "at": 1 })
+        .await?;
+    Ok(found.and_then(|d| d.get_datetime("at").ok().copied()))
+}
+
+/// Dates completed issues that have no `completedAt`; runs after the audit backfill,
+/// which guarantees an `updatedAt` to fall back on.
+async fn backfill_completed_at(
+    organizations: &Collection<Document>,
+    issues: &Collection<Document>,
+    transitions: &Collection<Document>,
+    events: &Collection<Document>,
+) -> anyhow::Result<()> {
+    let mut cursor = organizations.find(doc! {}).projection(doc! { "_id": 1, "workflow": 1 }).await?;
+    let mut orgs: Vec<Document> = Vec::new();
+    while let Some(d) = cursor.try_next().await? {
+        orgs.push(d);
+    }
+
+    let mut dated = 0;
+    for org in &orgs {
+        let Ok(org_id) = org.get_object_id("_id") else {
+            continue;
+        };
+        let keys = completed_keys(org);
+        if keys.is_empty() {
+            continue;
+        }
+        let mut cursor = issues
+            .find(doc! { "organizationId": org_id, "status": { "$in": &keys }, "completedAt": Bson::Null })
+            .projection(doc! { "_id": 1, "status": 1, "updatedAt": 1 })
+            .await?;
+        let mut pending: Vec<Document> = Vec::new();
+        while let Some(d) = cursor.try_next().await? {
+            pending.push(d);
+        }
+
+        for d in &pending {
+            let (Ok(id), Ok(status)) = (d.get_object_id("_id"), d.get_str("status")) else {
+                continue;
+            };
+            let mut at = latest_at(transitions, doc! { "issueId": id, "to": status }).await?;
+            if at.is_none() {
+                at = latest_at(
+                    events,
+                    doc! { "issueId": id, "changes": { "$elemMatch": { "field": "status", "new": status } } },
+                )
+                .await?;
+            }
+            let at = at
+                .or_else(|| d.get_datetime("updatedAt").ok().copied())
+                .unwrap_or_else(|| id.timestamp());
+            issues
+                .update_one(doc! { "_id": id }, doc! { "$set": { "completedAt": at } })
+                .await?;
+            dated += 1;
+        }
+    }
+    println!("issues: completedAt set on {dated}");
+    Ok(())
+}
+
+/// Normalizes user emails and reports the ones that collide once case is ignored.
+async fn normalize_emails(users: &Collection<Document>) -> anyhow::Result<()> {
+    let normalized = doc! { "$toLower": { "$trim": { "input": "$email" } } };
+    let mut cursor = users
+        .aggregate(vec![
+            doc! { "$group": {
+                "_id": &normalized,
+                "count": { "$sum": 1 },
+                "users": { "$push": { "id": "$_id", "email": "$email" } },
+            } },
+            doc! { "$match": { "count": { "$gt": 1 } } },
+        ])
+        .await?;
+    let mut colliding: Vec<String> = Vec::new();
+    while let Some(d) = cursor.try_next().await? {
+        let email = d.get_str("_id").unwrap_or_default().to_string();
+        println!("users: {} accounts share the email {email}:", d.get_i32("count").unwrap_or_default());
+        for u in d.get_array("users").map(|a| a.as_slice()).unwrap_or_default() {
+            if let Some(u) = u.as_document() {
+                let id = u.get_object_id("id").map(|id| id.to_hex()).unwrap_or_default();
+                println!("  {id} {}", u.get_str("email").unwrap_or_default());
+            }
+        }
+        colliding.push(email);
+    }
+
+    // colliding accounts keep their emails until someone decides which one stays
+    let lowered = users
+        .update_many(
+            doc! { "$expr": { "$and": [
+                { "$ne": ["$email", &normalized] },
+                { "$not": [{ "$in": [&normalized, &colliding] }] },
+            ] } },
+            vec![doc! { "$set": { "email": &normalized } }],
+        )
+        .await?;
+    println!("users: {} emails normalized, {} collisions", lowered.modified_count, colliding.len());
+
+    // same definition as in `server::api_server`
+    let index = IndexModel::builder()
+        .keys(doc! { "email": 1 })
+        .options(
+            IndexOptions::builder()
+                .name("users_email_unique".to_string())
+                .unique(true)
+                .collation(Collation::builder().locale("en").strength(CollationStrength::Secondary).build())
+                .build(),
+        )
+        .build();
+    match users.create_index(index).await {
+        Ok(_) => println!("users: unique email index in place"),
+        Err(e) => println!("users: unique email index not created ({e}); resolve the collisions above and rerun"),
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    backend::env::load_env();
+
+    let mongo_uri = backend::env::require_var("MONGO_URI")?;
+    let mongo_db = env_or("MONGO_DB", "dazabaze");
+
+    let client_options = ClientOptions::parse(&mongo_uri).await?;
+    let client = Client::with_options(client_options)?;
+    let db = client.database(&mongo_db);
+
+    number_issues(&db.collection("issues"), &db.collection("organizations")).await?;
+    backfill_issues(&db.collection("issues"), &db.collection("issue_events")).await?;
+    backfill(&db.collection("organizations"), Some("ownerId")).await?;
+    backfill_completed_at(
+        &db.collection("organizations"),
+        &db.collection("issues"),
+        &db.collection("status_transitions"),
+        &db.collection("issue_events"),
+    )
+    .await?;
+    backfill(&db.collection("labels"), None).await?;
+    backfill(&db.collection("views"), Some("ownerId")).await?;
+    normalize_emails(&db.collection("users")).await?;
+
+    println!("Migration complete. DB={}", mongo_db);
+    Ok(())
+}
@@ -0,0 +1,368 @@
+//! Compact filter language for issue listing, e.g.
+//! `status:in_progress assignee:me -label:wontfix parent:none`.
+//!
+//! - A filter is a whitespace-separated list of `field:value` terms, all of which must match
+//! - `-field:value` negates a term
+//! - `field:a,b` matches any of the values; values may be quoted (`label:"needs design"`)
+//! - Fields: `status`, `assignee` (`me`, `none`, user id), `label` (name, id or `none`),
+//!   `parent` (`none` or issue id), `priority` (`none` .. `urgent`)
+//!
+//! Parsing produces a typed AST; `compile` turns it into a MongoDB filter.
+
+use std::fmt;
+
+use mongodb::bson::{doc, oid::ObjectId, Bson, Document};
+
+use crate::models::{priority_rank, LabelDb, WorkflowStatus};
+use crate::workflow;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Status,
+    Assignee,
+    Label,
+    Parent,
+    Priority,
+}
+
+impl Field {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "status" => Some(Self::Status),
+            "assignee" => Some(Self::Assignee),
+            "label" => Some(Self::Label),
+            "parent" => Some(Self::Parent),
+            "priority" => Some(Self::Priority),
+            _ => None,
+        }
+    }
+}
+
+/// One `[-]field:value[,value...]` term.
+#[derive(Debug, Clone)]
+pub struct Term {
+    pub field: Field,
+    pub negated: bool,
+    pub values: Vec<String>,
+    /// The term as written, and its byte offset in the input (for error messages).
+    pub token: String,
+    pub position: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FilterQuery {
+    pub terms: Vec<Term>,
+}
+
+impl FilterQuery {
+    pub fn uses(&self, field: Field) -> bool {
+        self.terms.iter().any(|t| t.field == field)
+    }
+}
+
+#[derive(Debug)]
+pub struct FilterError {
+    pub message: String,
+    pub token: String,
+    pub position: usize,
+}
+
+impl FilterError {
+    fn at(term_token: &str, position: usize, message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            token: term_token.to_string(),
+            position,
+        }
+    }
+}
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Invalid filter at '{}' (position {}): {}",
+            self.token, self.position, self.message
+        )
+    }
+}
+
+/// Splits the input into `(offset, token)` pairs, keeping quoted values together.
+fn tokenize(input: &str) -> Result<Vec<(usize, String)>, FilterError> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut start = 0;
+    let mut quote_start: Option<usize> = None;
+
+    for (i, c) in input.char_indices() {
+        match c {
+            '"' => {
+                if current.is_empty() && quote_start.is_none() {
+                    start = i;
+                }
+                quote_start = match quote_start {
+                    Some(_) => None,
+                    None => Some(i),
+                };
+                current.push(c);
+            }
+            c if c.is_whitespace() && quote_start.is_none() => {
+                if !current.is_empty() {
+                    tokens.push((start, std::mem::take(&mut current)));
+                }
+            }
+            c => {
+                if current.is_empty() {
+                    start = i;
+                }
+                current.push(c);
+            }
+        }
+    }
+
+    if quote_start.is_some() {
+        return Err(FilterError::at(&current, start, "unterminated quote"));
+    }
+    if !current.is_empty() {
+        tokens.push((start, current));
+    }
+    Ok(tokens)
+}
+
+/// Splits `a,"b c",d` into values, honouring quotes.
+fn split_values(raw: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in raw.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            ',' if !quoted => values.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+    }
+    values.push(current);
+    values.into_iter().map(|v| v.trim().to_string()).collect()
+}
+
+/// Parses a filter string into its AST.
+pub fn parse(input: &str) -> Result<FilterQuery, FilterError> {
+    let mut terms = Vec::new();
+
+    for (position, token) in tokenize(input)? {
+        let (negated, body) = match token.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, token.as_str()),
+        };
+        let Some((name, raw_value)) = body.split_once(':') else {
+            return Err(FilterError::at(&token, position, "expected field:value"));
+        };
+        let Some(field) = Field::parse(&name.to_lowercase()) else {
+            return Err(FilterError::at(
+                &token,
+                position,
+                format!("unknown field '{name}' (expected status, assignee, label, parent or priority)"),
+            ));
+        };
+        let values = split_values(raw_value);
+        if values.iter().any(|v| v.is_empty()) {
+            return Err(FilterError::at(&token, position, "missing value"));
+        }
+
+        terms.push(Term {
+            field,
+            negated,
+            values,
+            token: token.clone(),
+            position,
+        });
+    }
+
+    Ok(FilterQuery { terms })
+}
+
+/// What `compile` needs to resolve names: the caller, the org's workflow and labels.
+pub struct FilterContext<'a> {
+    pub user_id: ObjectId,
+    pub workflow: &'a [WorkflowStatus],
+    pub labels: &'a [LabelDb],
+}
+
+fn matcher(field: &str, negated: bool, values: Vec<Bson>) -> Document {
+    let op = if negated { "$nin" } else { "$in" };
+    doc! { field: { op: values } }
+}
+
+fn object_id(term: &Term, value: &str, what: &str) -> Result<ObjectId, FilterError> {
+    ObjectId::parse_str(value).map_err(|_| FilterError::at(&term.token, term.position, format!("invalid {what} '{value}'")))
+}
+
+fn compile_term(term: &Term, ctx: &FilterContext<'_>) -> Result<Document, FilterError> {
+    let mut values: Vec<Bson> = Vec::new();
+
+    let field = match term.field {
+        Field::Status => {
+            for v in &term.values {
+                let Some(s) = workflow::resolve_status(ctx.workflow, v) else {
+                    return Err(FilterError::at(&term.token, term.position, format!("unknown status '{v}'")));
+                };
+                values.push(Bson::String(s.key.clone()));
+            }
+            "status"
+        }
+        Field::Assignee => {
+            for v in &term.values {
+                values.push(match v.to_lowercase().as_str() {
+                    "me" => Bson::ObjectId(ctx.user_id),
+                    "none" => Bson::Null,
+                    _ => Bson::ObjectId(object_id(term, v, "user id")?),
+                });
+            }
+            "assigneeId"
+        }
+        Field::Label => {
+            for v in &term.values {
+                if v.eq_ignore_ascii_case("none") {
+                    // missing or empty `labelIds`
+                    values.push(Bson::Null);
+                    values.push(Bson::Array(Vec::new()));
+                    continue;
+                }
+                let found = ctx
+                    .labels
+                    .iter()
+                    .find(|l| l.name.eq_ignore_ascii_case(v) || l.id.to_hex() == *v);
+                let Some(label) = found else {
+                    return Err(FilterError::at(&term.token, term.position, format!("unknown label '{v}'")));
+                };
+                values.push(Bson::ObjectId(label.id));
+            }
+            "labelIds"
+        }
+        Field::Parent => {
+            for v in &term.values {
+                values.push(if v.eq_ignore_ascii_case("none") {
+                    Bson::Null
+                } else {
+                    Bson::ObjectId(object_id(term, v, "issue id")?)
+                });
+            }
+            "parentIssueId"
+        }
+        Field::Priority => {
+            for v in &term.values {
+                let Some(rank) = priority_rank(&v.to_lowercase()) else {
+                    return Err(FilterError::at(&term.token, term.position, format!("unknown priority '{v}'")));
+                };
+                values.push(Bson::Int32(rank));
+                // issues created before priorities existed have no field at all
+                if rank == 0 {
+                    values.push(Bson::Null);
+                }
+            }
+            "priority"
+        }
+    };
+
+    Ok(matcher(field, term.negated, values))
+}
+
+/// Compiles a parsed filter into MongoDB conditions (to be combined with `$and`).
+pub fn compile(query: &FilterQuery, ctx: &FilterContext<'_>) -> Result<Vec<Document>, FilterError> {
+    query.terms.iter().map(|t| compile_term(t, ctx)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::default_workflow;
+
+    fn compile_str(input: &str) -> Result<Vec<Document>, FilterError> {
+        let workflow = default_workflow();
+        let ctx = FilterContext {
+            user_id: ObjectId::new(),
+            workflow: &workflow,
+            labels: &[],
+        };
+        compile(&parse(input)?, &ctx)
+    }
+
+    #[test]
+    fn parses_terms_with_positions() {
+        let query = parse("status:todo  assignee:me").unwrap();
+        assert_eq!(query.terms.len(), 2);
+        assert_eq!(query.terms[0].field, Field::Status);
+        assert_eq!(query.terms[0].values, ["todo"]);
+        assert_eq!(query.terms[1].field, Field::Assignee);
+        assert_eq!(query.terms[1].position, 13);
+    }
+
+    #[test]
+    fn keeps_quoted_values_together() {
+        let query = parse(r#"label:"needs design",bug status:todo"#).unwrap();
+        assert_eq!(query.terms.len(), 2);
+        assert_eq!(query.terms[0].values, ["needs design", "bug"]);
+        assert_eq!(query.terms[1].position, 25);
+    }
+
+    #[test]
+    fn negation() {
+        let query = parse(r#"-label:"won't fix" parent:none"#).unwrap();
+        assert!(query.terms[0].negated);
+        assert_eq!(query.terms[0].field, Field::Label);
+        assert_eq!(query.terms[0].values, ["won't fix"]);
+        assert!(!query.terms[1].negated);
+    }
+
+    #[test]
+    fn comma_lists() {
+        let query = parse("priority:high,urgent").unwrap();
+        assert_eq!(query.terms[0].values, ["high", "urgent"]);
+    }
+
+    #[test]
+    fn unknown_field() {
+        let err = parse("status:todo colour:red").unwrap_err();
+        assert_eq!(err.token, "colour:red");
+        assert_eq!(err.position, 12);
+        assert!(err.message.contains("unknown field 'colour'"));
+        assert_eq!(
+            err.to_string(),
+            "Invalid filter at 'colour:red' (position 12): unknown field 'colour' (expected status, assignee, label, parent or priority)"
+        );
+    }
+
+    #[test]
+    fn missing_value() {
+        for input in ["status:", "status:todo,", "priority:,high"] {
+            let err = parse(input).unwrap_err();
+            assert_eq!(err.message, "missing value", "{input}");
+            assert_eq!(err.position, 0);
+        }
+        assert_eq!(parse("assignee:me status").unwrap_err().message, "expected field:value");
+    }
+
+    #[test]
+    fn unterminated_quote() {
+        let err = parse(r#"status:todo label:"needs design"#).unwrap_err();
+        assert_eq!(err.message, "unterminated quote");
+        assert_eq!(err.token, r#"label:"needs design"#);
+        assert_eq!(err.position, 12);
+    }
+
+    #[test]
+    fn priority_none_includes_missing_field() {
+        let compiled = compile_str("priority:none").unwrap();
+        assert_eq!(compiled, [doc! { "priority": { "$in": [0, Bson::Null] } }]);
+
+        let compiled = compile_str("-priority:none,urgent").unwrap();
+        assert_eq!(compiled, [doc! { "priority": { "$nin": [0, Bson::Null, 4] } }]);
+    }
+
+    #[test]
+    fn compile_errors_point_at_the_term() {
+        let err = compile_str("assignee:me status:nope").unwrap_err();
+        assert_eq!(err.position, 12);
+        assert_eq!(err.message, "unknown status 'nope'");
+    }
+}
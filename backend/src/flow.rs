@@ -0,0 +1,112 @@
+//! Status-transition log and cumulative flow series.
+//!
+//! - Issue create/update append a `status_transitions` document whenever the status changes
+//! - Recording is best effort: a failed write is logged, the request still succeeds
+//! - Issues created before transitions were recorded are assumed to have held their
+//!   earliest known status since creation (`createdAt`)
+
+use std::collections::{BTreeMap, HashMap};
+
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use tracing::warn;
+
+use crate::models::{day_string, FlowDayOut, FlowOut, IssueDb, StatusTransitionDb, WorkflowStatus};
+use crate::server::AppState;
+use crate::workflow;
+
+pub const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// Records that `issue` (already saved) moved from `from` to its current status.
+/// `from` is `None` for a new issue; unchanged statuses are skipped.
+pub async fn record(data: &AppState, issue: &IssueDb, from: Option<&str>) {
+    if from == Some(issue.status.as_str()) {
+        return;
+    }
+    let transition = StatusTransitionDb {
+        id: ObjectId::new(),
+        issue_id: issue.id,
+        organization_id: issue.organization_id,
+        from: from.map(str::to_string),
+        to: issue.status.clone(),
+        at: DateTime::now(),
+    };
+    if let Err(e) = data.status_transitions.insert_one(&transition).await {
+        warn!("failed to record status transition for issue {}: {e}", issue.id);
+    }
+}
+
+/// Keeps an issue's transitions with it when it moves to another organization.
+pub async fn reassign(data: &AppState, issue_id: ObjectId, organization_id: ObjectId) {
+    if let Err(e) = data
+        .status_transitions
+        .update_many(doc! { "issueId": issue_id }, doc! { "$set": { "organizationId": organization_id } })
+        .await
+    {
+        warn!("failed to move status transitions of issue {issue_id}: {e}");
+    }
+}
+
+/// Status of `issue` just before `at`; `None` if it didn't exist yet.
+/// `transitions` must be sorted by time.
+fn status_at<'a>(issue: &'a IssueDb, transitions: &'a [StatusTransitionDb], at: DateTime) -> Option<&'a str> {
+    if issue.created_time() >= at {
+        return None;
+    }
+    let before = transitions.partition_point(|t| t.at < at);
+    match before {
+        0 => Some(transitions.first().and_then(|t| t.from.as_deref()).unwrap_or(&issue.status)),
+        n => Some(&transitions[n - 1].to),
+    }
+}
+
+/// Counts issues per status at the end of each day starting at `first_day`
+/// (midnight UTC). The current day is counted as of now.
+pub fn daily(
+    issues: &[IssueDb],
+    mut transitions: HashMap<ObjectId, Vec<StatusTransitionDb>>,
+    workflow: &[WorkflowStatus],
+    first_day: DateTime,
+    days: usize,
+) -> FlowOut {
+    for list in transitions.values_mut() {
+        list.sort_by_key(|t| t.at);
+    }
+    let closed = workflow::closed_keys(workflow);
+
+    let mut statuses: Vec<String> = workflow.iter().map(|s| s.key.clone()).collect();
+    let now = DateTime::now();
+    let mut out: Vec<FlowDayOut> = Vec::with_capacity(days);
+    for day in 0..days as i64 {
+        let start = first_day.timestamp_millis() + day * DAY_MS;
+        let end = DateTime::from_millis((start + DAY_MS).min(now.timestamp_millis()));
+
+        let mut counts: BTreeMap<String, i64> = statuses.iter().map(|s| (s.clone(), 0)).collect();
+        let (mut open, mut total) = (0, 0);
+        for issue in issues {
+            let list = transitions.get(&issue.id).map(Vec::as_slice).unwrap_or_default();
+            let Some(status) = status_at(issue, list, end) else {
+                continue;
+            };
+            if !statuses.iter().any(|s| s == status) {
+                statuses.push(status.to_string());
+                // earlier days simply had none
+                for d in &mut out {
+                    d.counts.insert(status.to_string(), 0);
+                }
+            }
+            *counts.entry(status.to_string()).or_default() += 1;
+            total += 1;
+            if !closed.iter().any(|c| c == status) {
+                open += 1;
+            }
+        }
+        out.push(FlowDayOut {
+            date: day_string(DateTime::from_millis(start)),
+            counts,
+            open,
+            total,
+        });
+    }
+
+    FlowOut { statuses, days: out }
+}
@@ -0,0 +1,86 @@
+//! Sub-issue tree helpers built on `$graphLookup` over `issues.parentIssueId`.
+
+use futures_util::TryStreamExt;
+use mongodb::bson::{self, doc, oid::ObjectId, Document};
+
+use crate::models::IssueDb;
+use crate::server::AppState;
+
+/// Ids of all ancestors of an issue (parent, grandparent, ...).
+///
+/// `$graphLookup` remembers visited documents, so this terminates even if the
+/// stored data already contains a cycle.
+pub async fn ancestor_ids(data: &AppState, issue_id: ObjectId, max_depth: usize) -> mongodb::error::Result<Vec<ObjectId>> {
+    let pipeline = vec![
+        doc! { "$match": { "_id": issue_id } },
+        doc! { "$graphLookup": {
+            "from": data.issues.name(),
+            "startWith": "$parentIssueId",
+            "connectFromField": "parentIssueId",
+            "connectToField": "_id",
+            "as": "ancestors",
+            "maxDepth": max_depth as i64,
+        } },
+        doc! { "$project": { "ancestors._id": 1 } },
+    ];
+
+    let mut cursor = data.issues.aggregate(pipeline).await?;
+    let Some(found) = cursor.try_next().await? else {
+        return Ok(Vec::new());
+    };
+    Ok(found
+        .get_array("ancestors")
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|a| a.as_document().and_then(|d| d.get_object_id("_id").ok()))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// All descendants of an issue, each paired with its distance below it (0 = direct child).
+pub async fn descendants(
+    data: &AppState,
+    issue: &IssueDb,
+    max_depth: Option<usize>,
+) -> mongodb::error::Result<Vec<(IssueDb, usize)>> {
+    let mut lookup = doc! {
+        "from": data.issues.name(),
+        "startWith": "$_id",
+        "connectFromField": "_id",
+        "connectToField": "parentIssueId",
+        "as": "descendants",
+        "depthField": "depth",
+        "restrictSearchWithMatch": { "organizationId": issue.organization_id },
+    };
+    if let Some(max) = max_depth {
+        lookup.insert("maxDepth", max as i64);
+    }
+    let pipeline = vec![
+        doc! { "$match": { "_id": issue.id } },
+        doc! { "$graphLookup": lookup },
+        doc! { "$project": { "descendants": 1 } },
+    ];
+
+    let mut cursor = data.issues.aggregate(pipeline).await?;
+    let Some(found) = cursor.try_next().await? else {
+        return Ok(Vec::new());
+    };
+
+    let mut out = Vec::new();
+    if let Ok(arr) = found.get_array("descendants") {
+        for d in arr.iter().filter_map(|d| d.as_document()) {
+            let depth = d.get_i64("depth").unwrap_or_default().max(0) as usize;
+            let mut d: Document = d.clone();
+            d.remove("depth");
+            out.push((bson::from_document::<IssueDb>(d)?, depth));
+        }
+    }
+    Ok(out)
+}
+
+/// Number of levels below an issue (0 = no children).
+pub async fn subtree_height(data: &AppState, issue: &IssueDb, max_depth: usize) -> mongodb::error::Result<usize> {
+    let found = descendants(data, issue, Some(max_depth)).await?;
+    Ok(found.iter().map(|(_, depth)| depth + 1).max().unwrap_or(0))
+}
@@ -0,0 +1,87 @@
+//! Per-issue change history.
+//!
+//! - Every issue create/update/delete stores an `issue_events` document
+//! - Each event holds a field-level diff (`old` -> `new`) and the acting user
+//! - Recording is best effort: a failed write is logged, the request still succeeds
+
+use mongodb::bson::{self, doc, oid::ObjectId, Bson, DateTime, Document};
+use tracing::warn;
+
+use crate::models::{FieldChange, IssueDb, IssueEventDb};
+use crate::server::AppState;
+
+fn to_doc(issue: Option<&IssueDb>) -> Document {
+    issue
+        .and_then(|i| bson::to_document(i).ok())
+        .unwrap_or_default()
+}
+
+/// Field-level diff between two versions of an issue (`None` = did not exist).
+pub fn diff(before: Option<&IssueDb>, after: Option<&IssueDb>) -> Vec<FieldChange> {
+    let before = to_doc(before);
+    let after = to_doc(after);
+
+    // the event itself records when and by whom
+    let mut fields: Vec<&String> = before
+        .keys()
+        .chain(after.keys())
+        .filter(|k| !matches!(k.as_str(), "_id" | "updatedAt" | "updatedBy"))
+        .collect();
+    fields.sort();
+    fields.dedup();
+
+    fields
+        .into_iter()
+        .filter_map(|field| {
+            let old = before.get(field).cloned().unwrap_or(Bson::Null);
+            let new = after.get(field).cloned().unwrap_or(Bson::Null);
+            (old != new).then(|| FieldChange {
+                field: field.clone(),
+                old,
+                new,
+            })
+        })
+        .collect()
+}
+
+/// Records a history event for an issue change. Updates without changes are skipped.
+pub async fn record(data: &AppState, actor_id: ObjectId, before: Option<&IssueDb>, after: Option<&IssueDb>) {
+    let Some(issue) = after.or(before) else {
+        return;
+    };
+    let kind = match (before, after) {
+        (None, Some(_)) => "created",
+        (Some(_), None) => "deleted",
+        _ => "updated",
+    };
+
+    let changes = diff(before, after);
+    if kind == "updated" && changes.is_empty() {
+        return;
+    }
+
+    let event = IssueEventDb {
+        id: ObjectId::new(),
+        issue_id: issue.id,
+        organization_id: issue.organization_id,
+        actor_id,
+        kind: kind.to_string(),
+        at: DateTime::now(),
+        changes,
+    };
+
+    if let Err(e) = data.issue_events.insert_one(&event).await {
+        warn!("failed to record history for issue {}: {e}", issue.id);
+    }
+}
+
+/// Keeps an issue's history with it when it moves to another organization.
+pub async fn reassign(data: &AppState, issue_id: ObjectId, organization_id: ObjectId) {
+    if let Err(e) = data
+        .issue_events
+        .update_many(doc! { "issueId": issue_id }, doc! { "$set": { "organizationId": organization_id } })
+        .await
+    {
+        warn!("failed to move history of issue {issue_id}: {e}");
+    }
+}
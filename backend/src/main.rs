@@ -1,8 +1,19 @@
 mod api;
 mod env;
+mod filter;
+mod flow;
 mod auth;
+mod hierarchy;
+mod history;
+mod mailer;
 mod models;
+mod pagination;
+mod rank;
+mod relations;
+mod search;
+mod sessions;
 mod server;
+mod workflow;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -1,11 +1,14 @@
-use mongodb::bson::oid::ObjectId;
-use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use mongodb::bson::{oid::ObjectId, Bson, DateTime};
+use mongodb::options::{Collation, CollationStrength};
+use serde::{Deserialize, Deserializer, Serialize};
 
 /// Data models for MongoDB + API DTOs.
 ///
 /// - Collections: `organizations`, `issues` (plus `users` for login)
 /// - Common field: `issues.organizationId` references `organizations._id`
-/// - Text index: created on `issues.title` + `issues.description` (see `bin/seed.rs`)
+/// - Text index: created on `issues.title` + `issues.description` (at server startup and in `bin/seed.rs`)
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UserDb {
     #[serde(rename = "_id")]
@@ -13,6 +16,10 @@ pub struct UserDb {
     pub email: String,
     pub name: String,
     pub password_hash: String,
+    /// Signed up but hasn't opened the verification link yet. Accounts from
+    /// before verification existed count as verified.
+    #[serde(default)]
+    pub unverified: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -21,6 +28,7 @@ pub struct UserOut {
     pub id: String,
     pub email: String,
     pub name: String,
+    pub unverified: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -38,10 +46,110 @@ pub struct LoginIn {
 
 #[derive(Debug, Serialize)]
 pub struct AuthOut {
+    /// Short-lived access token (`Authorization: Bearer ...`).
     pub token: String,
+    /// Seconds until `token` expires.
+    #[serde(rename = "expiresIn")]
+    pub expires_in: i64,
+    /// Single-use token for `POST /api/auth/refresh`.
+    #[serde(rename = "refreshToken")]
+    pub refresh_token: String,
     pub user: UserOut,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct RefreshIn {
+    #[serde(rename = "refreshToken")]
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LogoutIn {
+    #[serde(rename = "refreshToken")]
+    pub refresh_token: Option<String>,
+}
+
+/// A login session (see `sessions.rs`). Deleting it revokes its tokens.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionDb {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "userId")]
+    pub user_id: ObjectId,
+    /// SHA-256 of the current refresh token.
+    #[serde(rename = "refreshHash")]
+    pub refresh_hash: String,
+    /// SHA-256 of the refresh token it replaced, to detect reuse.
+    #[serde(rename = "previousRefreshHash")]
+    pub previous_refresh_hash: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime,
+    /// Moves forward on every refresh; the TTL index removes expired sessions.
+    #[serde(rename = "expiresAt")]
+    pub expires_at: DateTime,
+    /// Client that started or last refreshed the session.
+    #[serde(rename = "userAgent", default)]
+    pub user_agent: Option<String>,
+    #[serde(default)]
+    pub ip: Option<String>,
+    /// Last authenticated request (updated at most once a minute).
+    #[serde(rename = "lastSeenAt", default)]
+    pub last_seen_at: Option<DateTime>,
+}
+
+/// A pending password reset; deleted when used (single-use) or by the TTL index.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PasswordResetDb {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "userId")]
+    pub user_id: ObjectId,
+    /// SHA-256 of the token sent by mail.
+    #[serde(rename = "tokenHash")]
+    pub token_hash: String,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: DateTime,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VerifyEmailIn {
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ForgotPasswordIn {
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResetPasswordIn {
+    pub token: String,
+    pub password: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionOut {
+    #[serde(rename = "_id")]
+    pub id: String,
+    #[serde(rename = "userAgent")]
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    #[serde(rename = "lastSeenAt")]
+    pub last_seen_at: String,
+    /// The session making this request.
+    pub current: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PasswordChangeIn {
+    #[serde(rename = "currentPassword")]
+    pub current_password: String,
+    #[serde(rename = "newPassword")]
+    pub new_password: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OrganizationDb {
     #[serde(rename = "_id")]
@@ -52,6 +160,41 @@ pub struct OrganizationDb {
     pub owner_id: ObjectId,
     #[serde(rename = "memberIds")]
     pub member_ids: Vec<ObjectId>,
+    /// Last allocated issue number (incremented atomically on issue create).
+    #[serde(rename = "issueCounter", default)]
+    pub issue_counter: i64,
+    /// Ordered workflow statuses. Empty means the default workflow (see `workflow.rs`).
+    #[serde(default)]
+    pub workflow: Vec<WorkflowStatus>,
+    /// Only users with a verified email can be added as members.
+    #[serde(rename = "requireVerifiedMembers", default)]
+    pub require_verified_members: bool,
+    /// Audit fields; missing on documents not yet backfilled by `bin/migrate.rs`.
+    #[serde(rename = "createdAt", default)]
+    pub created_at: Option<DateTime>,
+    #[serde(rename = "createdBy", default)]
+    pub created_by: Option<ObjectId>,
+    #[serde(rename = "updatedAt", default)]
+    pub updated_at: Option<DateTime>,
+    #[serde(rename = "updatedBy", default)]
+    pub updated_by: Option<ObjectId>,
+}
+
+/// One column of an organization's workflow (stored inline on the organization).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowStatus {
+    pub key: String,
+    pub name: String,
+    /// One of `unstarted`, `started`, `completed`, `cancelled`.
+    pub category: String,
+    /// Status keys this status may move to. Empty means any status is allowed.
+    #[serde(default)]
+    pub transitions: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WorkflowIn {
+    pub statuses: Vec<WorkflowStatus>,
 }
 
 #[derive(Debug, Serialize)]
@@ -64,12 +207,30 @@ pub struct OrganizationOut {
     pub owner_id: String,
     #[serde(rename = "memberIds")]
     pub member_ids: Vec<String>,
+    #[serde(rename = "requireVerifiedMembers")]
+    pub require_verified_members: bool,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    #[serde(rename = "createdBy")]
+    pub created_by: Option<String>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: String,
+    #[serde(rename = "updatedBy")]
+    pub updated_by: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct OrganizationCreateIn {
     pub name: String,
     pub key: String,
+    #[serde(rename = "requireVerifiedMembers")]
+    pub require_verified_members: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OrganizationPatchIn {
+    #[serde(rename = "requireVerifiedMembers")]
+    pub require_verified_members: Option<bool>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -77,12 +238,18 @@ pub struct OrganizationAddMemberIn {
     pub email: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IssueDb {
     #[serde(rename = "_id")]
     pub id: ObjectId,
     #[serde(rename = "organizationId")]
     pub organization_id: ObjectId,
+    /// Sequential number within the organization (`42` in `ACME-42`).
+    #[serde(default)]
+    pub number: i64,
+    /// Human-readable key, e.g. `ACME-42`.
+    #[serde(default)]
+    pub key: String,
     pub title: String,
     pub description: String,
     pub status: String,
@@ -90,6 +257,40 @@ pub struct IssueDb {
     pub assignee_id: Option<ObjectId>,
     #[serde(rename = "parentIssueId")]
     pub parent_issue_id: Option<ObjectId>,
+    /// Stored as a rank (0 = none .. 4 = urgent) so it sorts correctly; see `PRIORITIES`.
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default)]
+    pub estimate: Option<f64>,
+    #[serde(rename = "labelIds", default)]
+    pub label_ids: Vec<ObjectId>,
+    /// When the issue last moved into a `completed` status; cleared when it leaves one.
+    #[serde(rename = "completedAt", default)]
+    pub completed_at: Option<DateTime>,
+    /// Manual board order within the organization (see `rank.rs`); empty for unranked legacy issues.
+    #[serde(default)]
+    pub rank: String,
+    /// Calendar dates, stored as midnight UTC.
+    #[serde(rename = "startDate", default)]
+    pub start_date: Option<DateTime>,
+    #[serde(rename = "dueDate", default)]
+    pub due_date: Option<DateTime>,
+    /// Audit fields; missing on documents not yet backfilled by `bin/migrate.rs`.
+    #[serde(rename = "createdAt", default)]
+    pub created_at: Option<DateTime>,
+    #[serde(rename = "createdBy", default)]
+    pub created_by: Option<ObjectId>,
+    #[serde(rename = "updatedAt", default)]
+    pub updated_at: Option<DateTime>,
+    #[serde(rename = "updatedBy", default)]
+    pub updated_by: Option<ObjectId>,
+}
+
+impl IssueDb {
+    /// `createdAt`, or the `_id` time for issues written before it was recorded.
+    pub fn created_time(&self) -> DateTime {
+        self.created_at.unwrap_or(self.id.timestamp())
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -98,6 +299,8 @@ pub struct IssueOut {
     pub id: String,
     #[serde(rename = "organizationId")]
     pub organization_id: String,
+    pub number: i64,
+    pub key: String,
     pub title: String,
     pub description: String,
     pub status: String,
@@ -105,6 +308,85 @@ pub struct IssueOut {
     pub assignee_id: Option<String>,
     #[serde(rename = "parentIssueId")]
     pub parent_issue_id: Option<String>,
+    pub priority: &'static str,
+    pub estimate: Option<f64>,
+    #[serde(rename = "labelIds")]
+    pub label_ids: Vec<String>,
+    #[serde(rename = "completedAt")]
+    pub completed_at: Option<String>,
+    pub rank: String,
+    /// `YYYY-MM-DD`.
+    #[serde(rename = "startDate")]
+    pub start_date: Option<String>,
+    /// `YYYY-MM-DD`.
+    #[serde(rename = "dueDate")]
+    pub due_date: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    #[serde(rename = "createdBy")]
+    pub created_by: Option<String>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: String,
+    #[serde(rename = "updatedBy")]
+    pub updated_by: Option<String>,
+}
+
+/// An issue with its nested sub-issues (`GET /api/issues/{id}/tree`).
+#[derive(Debug, Serialize)]
+pub struct IssueTreeOut {
+    #[serde(flatten)]
+    pub issue: IssueOut,
+    pub children: Vec<IssueTreeOut>,
+}
+
+/// An issue with its relations (`GET /api/issues/{id}`).
+#[derive(Debug, Serialize)]
+pub struct IssueDetailOut {
+    #[serde(flatten)]
+    pub issue: IssueOut,
+    pub relations: Vec<IssueRelationOut>,
+}
+
+/// A typed link between two issues of one organization, stored once in its
+/// canonical direction (`blocks`, `duplicates` or `relates_to`; see `relations.rs`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueRelationDb {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "organizationId")]
+    pub organization_id: ObjectId,
+    pub kind: String,
+    #[serde(rename = "sourceId")]
+    pub source_id: ObjectId,
+    #[serde(rename = "targetId")]
+    pub target_id: ObjectId,
+    #[serde(rename = "createdBy")]
+    pub created_by: ObjectId,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime,
+}
+
+/// A relation as seen from one of its issues.
+#[derive(Debug, Serialize)]
+pub struct IssueRelationOut {
+    #[serde(rename = "_id")]
+    pub id: String,
+    /// `blocks`, `blocked_by`, `duplicates`, `duplicated_by` or `relates_to`.
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    #[serde(rename = "issueId")]
+    pub issue_id: String,
+    pub key: String,
+    pub title: String,
+    pub status: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IssueRelationIn {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(rename = "issueId")]
+    pub issue_id: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -118,6 +400,87 @@ pub struct IssueIn {
     pub assignee_id: Option<String>,
     #[serde(rename = "parentIssueId")]
     pub parent_issue_id: Option<String>,
+    pub priority: Option<String>,
+    pub estimate: Option<f64>,
+    #[serde(rename = "labelIds")]
+    pub label_ids: Option<Vec<String>>,
+    /// `YYYY-MM-DD`; must not be after `dueDate`.
+    #[serde(rename = "startDate")]
+    pub start_date: Option<String>,
+    #[serde(rename = "dueDate")]
+    pub due_date: Option<String>,
+}
+
+/// Partial issue update (`PATCH /api/issues/{id}`).
+///
+/// Omitted fields stay unchanged. For nullable fields an explicit `null`
+/// clears the value, which is why they are `Option<Option<_>>`.
+#[derive(Debug, Default, Deserialize)]
+pub struct IssuePatchIn {
+    #[serde(rename = "organizationId")]
+    pub organization_id: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub status: Option<String>,
+    #[serde(rename = "assigneeId", default, deserialize_with = "double_option")]
+    pub assignee_id: Option<Option<String>>,
+    #[serde(rename = "parentIssueId", default, deserialize_with = "double_option")]
+    pub parent_issue_id: Option<Option<String>>,
+    pub priority: Option<String>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub estimate: Option<Option<f64>>,
+    #[serde(rename = "labelIds")]
+    pub label_ids: Option<Vec<String>>,
+    #[serde(rename = "startDate", default, deserialize_with = "double_option")]
+    pub start_date: Option<Option<String>>,
+    #[serde(rename = "dueDate", default, deserialize_with = "double_option")]
+    pub due_date: Option<Option<String>>,
+}
+
+/// A full `PUT` body is a patch that sets every required field.
+/// `priority`, `estimate`, `labelIds` and the dates are still optional there (omitted = unchanged).
+impl From<IssueIn> for IssuePatchIn {
+    fn from(i: IssueIn) -> Self {
+        Self {
+            organization_id: Some(i.organization_id),
+            title: Some(i.title),
+            description: Some(i.description),
+            status: Some(i.status),
+            assignee_id: Some(i.assignee_id),
+            parent_issue_id: Some(i.parent_issue_id),
+            priority: i.priority,
+            estimate: i.estimate.map(Some),
+            label_ids: i.label_ids,
+            start_date: i.start_date.map(Some),
+            due_date: i.due_date.map(Some),
+        }
+    }
+}
+
+/// Distinguishes "field missing" (`None`) from "field is null" (`Some(None)`).
+fn double_option<'de, T, D>(de: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(de).map(Some)
+}
+
+/// Body of `POST /api/issues/{id}/move`: place the issue between two neighbours.
+#[derive(Debug, Deserialize)]
+pub struct IssueMoveIn {
+    /// Issue that should end up directly above (omit for the top).
+    pub after: Option<String>,
+    /// Issue that should end up directly below (omit for the bottom).
+    pub before: Option<String>,
+    /// Optional new status, e.g. when dragging to another board column.
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteIssueQuery {
+    /// What happens to sub-issues: `reparent` (default), `cascade` or `restrict`.
+    pub mode: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -125,14 +488,515 @@ pub struct SearchQuery {
     pub q: Option<String>,
     #[serde(rename = "organizationId")]
     pub organization_id: Option<String>,
+    pub limit: Option<i64>,
+    /// `nextCursor` from the previous page.
+    pub after: Option<String>,
+}
+
+/// A search result: the issue plus how and how well it matched.
+#[derive(Debug, Serialize)]
+pub struct SearchHitOut {
+    #[serde(flatten)]
+    pub issue: IssueOut,
+    /// Relevance; only comparable between hits of the same `match` kind.
+    pub score: f64,
+    /// `text` (full-text index), `prefix` or `fuzzy` (fallback when the index finds nothing).
+    #[serde(rename = "match")]
+    pub match_type: &'static str,
+    pub highlights: SearchHighlightsOut,
+}
+
+/// HTML-escaped text with matches wrapped in `<mark>`; `null` when the field didn't match.
+#[derive(Debug, Serialize)]
+pub struct SearchHighlightsOut {
+    pub title: Option<String>,
+    pub description: Option<String>,
+}
+
+/// A cross-organization search result (`GET /api/search`), tagged with its organization.
+#[derive(Debug, Serialize)]
+pub struct GlobalSearchHitOut {
+    #[serde(flatten)]
+    pub hit: SearchHitOut,
+    pub organization: OrganizationRefOut,
+}
+
+/// Just enough of an organization to label a result.
+#[derive(Debug, Clone, Serialize)]
+pub struct OrganizationRefOut {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub name: String,
+    pub key: String,
 }
 
 #[derive(Debug, Deserialize)]
+pub struct StatsQuery {
+    /// Start of the created/closed window (RFC 3339 or `YYYY-MM-DD`); default 30 days before `until`.
+    pub since: Option<String>,
+    /// End of the window (exclusive); default now.
+    pub until: Option<String>,
+}
+
+/// Dashboard numbers for one organization (`GET /api/organizations/{id}/stats`).
+/// "Open" means any status outside the `completed` and `cancelled` categories.
+#[derive(Debug, Serialize)]
+pub struct OrganizationStatsOut {
+    pub total: i64,
+    pub open: i64,
+    /// Every workflow status in order (zero counts included), then unknown statuses.
+    #[serde(rename = "byStatus")]
+    pub by_status: Vec<StatusCountOut>,
+    #[serde(rename = "byAssignee")]
+    pub by_assignee: Vec<AssigneeCountOut>,
+    pub unassigned: OpenTotalOut,
+    /// Parents with open sub-issues, most open first.
+    #[serde(rename = "openSubIssues")]
+    pub open_sub_issues: Vec<ParentCountOut>,
+    pub window: StatsWindowOut,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatusCountOut {
+    pub status: String,
+    pub name: String,
+    /// `null` for statuses no longer in the workflow.
+    pub category: Option<String>,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AssigneeCountOut {
+    #[serde(rename = "assigneeId")]
+    pub assignee_id: String,
+    /// `null` if the user no longer exists.
+    pub name: Option<String>,
+    pub open: i64,
+    pub total: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OpenTotalOut {
+    pub open: i64,
+    pub total: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ParentCountOut {
+    #[serde(rename = "parentIssueId")]
+    pub parent_issue_id: String,
+    pub key: Option<String>,
+    pub title: Option<String>,
+    pub open: i64,
+    pub total: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatsWindowOut {
+    pub since: String,
+    pub until: String,
+    pub created: i64,
+    /// Issues that entered a `completed` status within the window.
+    pub closed: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FlowQuery {
+    /// First day (`YYYY-MM-DD`, UTC); default 30 days before `to`.
+    pub from: Option<String>,
+    /// Last day (inclusive); default today.
+    pub to: Option<String>,
+    /// Only count sub-issues (at any depth) of this issue.
+    #[serde(rename = "parentIssueId")]
+    pub parent_issue_id: Option<String>,
+}
+
+/// Cumulative flow / burndown series (`GET /api/organizations/{id}/flow`).
+#[derive(Debug, Serialize)]
+pub struct FlowOut {
+    /// Status keys in workflow order (then statuses no longer in the workflow).
+    pub statuses: Vec<String>,
+    pub days: Vec<FlowDayOut>,
+}
+
+/// Issue counts at the end of one UTC day.
+#[derive(Debug, Serialize)]
+pub struct FlowDayOut {
+    /// `YYYY-MM-DD`.
+    pub date: String,
+    /// Issues per status key; every key of `statuses` is present.
+    pub counts: BTreeMap<String, i64>,
+    /// Issues not in a `completed`/`cancelled` status (the burndown line).
+    pub open: i64,
+    pub total: i64,
+}
+
+/// One page of a cursor-paginated list (see `pagination.rs`).
+#[derive(Debug, Serialize)]
+pub struct PageOut<T> {
+    pub items: Vec<T>,
+    /// Pass as `after` to fetch the next page; `null` on the last page.
+    #[serde(rename = "nextCursor")]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GlobalSearchQuery {
+    pub q: Option<String>,
+    pub limit: Option<i64>,
+    /// `nextCursor` from the previous page.
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
 pub struct ListIssuesQuery {
     #[serde(rename = "organizationId")]
     pub organization_id: Option<String>,
     #[serde(rename = "parentIssueId")]
     pub parent_issue_id: Option<String>,
+    /// Comma-separated priority names, e.g. `high,urgent`.
+    pub priority: Option<String>,
+    #[serde(rename = "estimateMin")]
+    pub estimate_min: Option<f64>,
+    #[serde(rename = "estimateMax")]
+    pub estimate_max: Option<f64>,
+    #[serde(rename = "labelId")]
+    pub label_id: Option<String>,
+    /// `true`: due date has passed and the issue is still open; `false`: everything else.
+    pub overdue: Option<bool>,
+    /// Due strictly before this date (`YYYY-MM-DD`).
+    #[serde(rename = "dueBefore")]
+    pub due_before: Option<String>,
+    /// Due strictly after this date (`YYYY-MM-DD`).
+    #[serde(rename = "dueAfter")]
+    pub due_after: Option<String>,
+    /// Filter expression, e.g. `status:in_progress assignee:me -label:wontfix` (see `filter.rs`).
+    pub filter: Option<String>,
+    /// `created` (default), `priority`, `estimate`, `rank` or `due`.
+    pub sort: Option<String>,
+    /// `desc` (default; `asc` for `rank`) or `asc`.
+    pub order: Option<String>,
+    pub limit: Option<i64>,
+    /// `nextCursor` from the previous page.
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LabelDb {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "organizationId")]
+    pub organization_id: ObjectId,
+    pub name: String,
+    /// Hex color, e.g. `#5e6ad2`.
+    pub color: String,
+    /// Audit fields; missing on documents not yet backfilled by `bin/migrate.rs`.
+    #[serde(rename = "createdAt", default)]
+    pub created_at: Option<DateTime>,
+    #[serde(rename = "createdBy", default)]
+    pub created_by: Option<ObjectId>,
+    #[serde(rename = "updatedAt", default)]
+    pub updated_at: Option<DateTime>,
+    #[serde(rename = "updatedBy", default)]
+    pub updated_by: Option<ObjectId>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LabelOut {
+    #[serde(rename = "_id")]
+    pub id: String,
+    #[serde(rename = "organizationId")]
+    pub organization_id: String,
+    pub name: String,
+    pub color: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    #[serde(rename = "createdBy")]
+    pub created_by: Option<String>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: String,
+    #[serde(rename = "updatedBy")]
+    pub updated_by: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LabelIn {
+    #[serde(rename = "organizationId")]
+    pub organization_id: String,
+    pub name: String,
+    pub color: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LabelUpdateIn {
+    pub name: String,
+    pub color: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListLabelsQuery {
+    #[serde(rename = "organizationId")]
+    pub organization_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommentDb {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "issueId")]
+    pub issue_id: ObjectId,
+    /// Denormalized from the issue so organization deletes can clean up comments.
+    #[serde(rename = "organizationId")]
+    pub organization_id: ObjectId,
+    #[serde(rename = "authorId")]
+    pub author_id: ObjectId,
+    pub body: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime,
+    #[serde(rename = "editedAt")]
+    pub edited_at: Option<DateTime>,
+    #[serde(rename = "editCount", default)]
+    pub edit_count: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CommentOut {
+    #[serde(rename = "_id")]
+    pub id: String,
+    #[serde(rename = "issueId")]
+    pub issue_id: String,
+    #[serde(rename = "authorId")]
+    pub author_id: String,
+    pub body: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    #[serde(rename = "editedAt")]
+    pub edited_at: Option<String>,
+    #[serde(rename = "editCount")]
+    pub edit_count: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommentIn {
+    pub body: String,
+}
+
+/// One entry of an issue's change history (see `history.rs`).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IssueEventDb {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "issueId")]
+    pub issue_id: ObjectId,
+    #[serde(rename = "organizationId")]
+    pub organization_id: ObjectId,
+    #[serde(rename = "actorId")]
+    pub actor_id: ObjectId,
+    /// `created`, `updated` or `deleted`.
+    pub kind: String,
+    pub at: DateTime,
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: Bson,
+    pub new: Bson,
+}
+
+/// One status change of an issue (`from` is `null` for the status it was created with).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusTransitionDb {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "issueId")]
+    pub issue_id: ObjectId,
+    #[serde(rename = "organizationId")]
+    pub organization_id: ObjectId,
+    pub from: Option<String>,
+    pub to: String,
+    pub at: DateTime,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IssueEventOut {
+    #[serde(rename = "_id")]
+    pub id: String,
+    #[serde(rename = "issueId")]
+    pub issue_id: String,
+    #[serde(rename = "organizationId")]
+    pub organization_id: String,
+    #[serde(rename = "actorId")]
+    pub actor_id: String,
+    pub kind: String,
+    pub at: String,
+    pub changes: Vec<FieldChangeOut>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FieldChangeOut {
+    pub field: String,
+    pub old: serde_json::Value,
+    pub new: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ActivityQuery {
+    pub limit: Option<i64>,
+    /// Event id to page backwards from (exclusive).
+    pub before: Option<String>,
+}
+
+/// A saved issue query (filter + sort), private to its owner unless `shared`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ViewDb {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "organizationId")]
+    pub organization_id: ObjectId,
+    #[serde(rename = "ownerId")]
+    pub owner_id: ObjectId,
+    pub name: String,
+    /// Filter expression (see `filter.rs`); empty means "all issues".
+    pub filter: String,
+    pub sort: String,
+    pub order: String,
+    /// Visible to every member of the organization, not just the owner.
+    pub shared: bool,
+    /// Audit fields; missing on documents not yet backfilled by `bin/migrate.rs`.
+    #[serde(rename = "createdAt", default)]
+    pub created_at: Option<DateTime>,
+    #[serde(rename = "createdBy", default)]
+    pub created_by: Option<ObjectId>,
+    #[serde(rename = "updatedAt", default)]
+    pub updated_at: Option<DateTime>,
+    #[serde(rename = "updatedBy", default)]
+    pub updated_by: Option<ObjectId>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ViewOut {
+    #[serde(rename = "_id")]
+    pub id: String,
+    #[serde(rename = "organizationId")]
+    pub organization_id: String,
+    #[serde(rename = "ownerId")]
+    pub owner_id: String,
+    pub name: String,
+    pub filter: String,
+    pub sort: String,
+    pub order: String,
+    pub shared: bool,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    #[serde(rename = "createdBy")]
+    pub created_by: Option<String>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: String,
+    #[serde(rename = "updatedBy")]
+    pub updated_by: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ViewIn {
+    #[serde(rename = "organizationId")]
+    pub organization_id: String,
+    pub name: String,
+    pub filter: Option<String>,
+    pub sort: Option<String>,
+    pub order: Option<String>,
+    pub shared: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ViewUpdateIn {
+    pub name: String,
+    pub filter: Option<String>,
+    pub sort: Option<String>,
+    pub order: Option<String>,
+    pub shared: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListViewsQuery {
+    #[serde(rename = "organizationId")]
+    pub organization_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ViewIssuesQuery {
+    pub limit: Option<i64>,
+    pub after: Option<String>,
+}
+
+/// Issue priorities, indexed by their stored rank.
+pub const PRIORITIES: [&str; 5] = ["none", "low", "medium", "high", "urgent"];
+
+pub fn priority_rank(name: &str) -> Option<i32> {
+    PRIORITIES.iter().position(|p| *p == name).map(|i| i as i32)
+}
+
+pub fn priority_name(rank: i32) -> &'static str {
+    PRIORITIES.get(rank as usize).copied().unwrap_or("none")
+}
+
+/// Formats a BSON datetime as RFC 3339 for API responses.
+pub fn rfc3339(dt: DateTime) -> String {
+    dt.try_to_rfc3339_string().unwrap_or_default()
+}
+
+/// `createdAt`/`updatedAt` for API responses. Documents not yet backfilled by
+/// `bin/migrate.rs` fall back to the `_id` time.
+fn audit_times(id: ObjectId, created_at: Option<DateTime>, updated_at: Option<DateTime>) -> (String, String) {
+    let created_at = created_at.unwrap_or(id.timestamp());
+    (rfc3339(created_at), rfc3339(updated_at.unwrap_or(created_at)))
+}
+
+/// Formats a BSON datetime as a calendar date (`YYYY-MM-DD`, UTC).
+pub fn day_string(dt: DateTime) -> String {
+    chrono::DateTime::from_timestamp_millis(dt.timestamp_millis())
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+/// Parses a client-supplied time: RFC 3339, or a plain `YYYY-MM-DD` date (midnight UTC).
+pub fn parse_time(raw: &str) -> Option<DateTime> {
+    let raw = raw.trim();
+    if raw.len() == 10 {
+        return DateTime::parse_rfc3339_str(format!("{raw}T00:00:00Z")).ok();
+    }
+    DateTime::parse_rfc3339_str(raw).ok()
+}
+
+/// Converts a stored BSON value to plain JSON (ids as hex, dates as RFC 3339).
+pub fn bson_to_json(value: Bson) -> serde_json::Value {
+    match value {
+        Bson::ObjectId(oid) => serde_json::Value::String(oid.to_hex()),
+        Bson::DateTime(dt) => serde_json::Value::String(rfc3339(dt)),
+        Bson::Array(items) => serde_json::Value::Array(items.into_iter().map(bson_to_json).collect()),
+        other => other.into_relaxed_extjson(),
+    }
+}
+
+/// Canonical form of an email address: trimmed and lowercased.
+pub fn normalize_email(raw: &str) -> String {
+    raw.trim().to_lowercase()
+}
+
+/// Case-insensitive matching, same as the unique `users.email` index. Lookups
+/// by email pass it so they can use that index (and still find accounts stored
+/// before emails were normalized).
+pub fn email_collation() -> Collation {
+    Collation::builder()
+        .locale("en")
+        .strength(CollationStrength::Secondary)
+        .build()
+}
+
+/// Formats an issue key from the organization key and the issue number (`ACME-42`).
+pub fn issue_key(org_key: &str, number: i64) -> String {
+    format!("{org_key}-{number}")
 }
 
 impl From<UserDb> for UserOut {
@@ -141,33 +1005,126 @@ impl From<UserDb> for UserOut {
             id: u.id.to_hex(),
             email: u.email,
             name: u.name,
+            unverified: u.unverified,
         }
     }
 }
 
 impl From<OrganizationDb> for OrganizationOut {
     fn from(o: OrganizationDb) -> Self {
+        let (created_at, updated_at) = audit_times(o.id, o.created_at, o.updated_at);
         Self {
             id: o.id.to_hex(),
             name: o.name,
             key: o.key,
             owner_id: o.owner_id.to_hex(),
             member_ids: o.member_ids.into_iter().map(|x| x.to_hex()).collect(),
+            require_verified_members: o.require_verified_members,
+            created_at,
+            created_by: o.created_by.map(|x| x.to_hex()),
+            updated_at,
+            updated_by: o.updated_by.map(|x| x.to_hex()),
         }
     }
 }
 
 impl From<IssueDb> for IssueOut {
     fn from(i: IssueDb) -> Self {
+        let (created_at, updated_at) = audit_times(i.id, i.created_at, i.updated_at);
         Self {
             id: i.id.to_hex(),
             organization_id: i.organization_id.to_hex(),
+            number: i.number,
+            key: i.key,
             title: i.title,
             description: i.description,
             status: i.status,
             assignee_id: i.assignee_id.map(|x| x.to_hex()),
             parent_issue_id: i.parent_issue_id.map(|x| x.to_hex()),
+            priority: priority_name(i.priority),
+            estimate: i.estimate,
+            label_ids: i.label_ids.into_iter().map(|x| x.to_hex()).collect(),
+            completed_at: i.completed_at.map(rfc3339),
+            rank: i.rank,
+            start_date: i.start_date.map(day_string),
+            due_date: i.due_date.map(day_string),
+            created_at,
+            created_by: i.created_by.map(|x| x.to_hex()),
+            updated_at,
+            updated_by: i.updated_by.map(|x| x.to_hex()),
+        }
+    }
+}
+
+impl From<LabelDb> for LabelOut {
+    fn from(l: LabelDb) -> Self {
+        let (created_at, updated_at) = audit_times(l.id, l.created_at, l.updated_at);
+        Self {
+            id: l.id.to_hex(),
+            organization_id: l.organization_id.to_hex(),
+            name: l.name,
+            color: l.color,
+            created_at,
+            created_by: l.created_by.map(|x| x.to_hex()),
+            updated_at,
+            updated_by: l.updated_by.map(|x| x.to_hex()),
+        }
+    }
+}
+
+
+impl From<CommentDb> for CommentOut {
+    fn from(c: CommentDb) -> Self {
+        Self {
+            id: c.id.to_hex(),
+            issue_id: c.issue_id.to_hex(),
+            author_id: c.author_id.to_hex(),
+            body: c.body,
+            created_at: rfc3339(c.created_at),
+            edited_at: c.edited_at.map(rfc3339),
+            edit_count: c.edit_count,
         }
     }
 }
 
+impl From<IssueEventDb> for IssueEventOut {
+    fn from(e: IssueEventDb) -> Self {
+        Self {
+            id: e.id.to_hex(),
+            issue_id: e.issue_id.to_hex(),
+            organization_id: e.organization_id.to_hex(),
+            actor_id: e.actor_id.to_hex(),
+            kind: e.kind,
+            at: rfc3339(e.at),
+            changes: e
+                .changes
+                .into_iter()
+                .map(|c| FieldChangeOut {
+                    field: c.field,
+                    old: bson_to_json(c.old),
+                    new: bson_to_json(c.new),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<ViewDb> for ViewOut {
+    fn from(v: ViewDb) -> Self {
+        let (created_at, updated_at) = audit_times(v.id, v.created_at, v.updated_at);
+        Self {
+            id: v.id.to_hex(),
+            organization_id: v.organization_id.to_hex(),
+            owner_id: v.owner_id.to_hex(),
+            name: v.name,
+            filter: v.filter,
+            sort: v.sort,
+            order: v.order,
+            shared: v.shared,
+            created_at,
+            created_by: v.created_by.map(|x| x.to_hex()),
+            updated_at,
+            updated_by: v.updated_by.map(|x| x.to_hex()),
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! Opaque keyset cursors for paginated list endpoints.
+//!
+//! - Results are always ordered by `(sort field, _id)` in one direction
+//! - `nextCursor` encodes the sort value and `_id` of the last returned item
+//! - The next page asks for everything strictly "after" that pair, so inserts
+//!   and deletes between requests never duplicate or skip items
+
+use mongodb::bson::{doc, oid::ObjectId, Bson, Document};
+
+pub const DEFAULT_LIMIT: i64 = 50;
+pub const MAX_LIMIT: i64 = 200;
+
+/// Clamps a client-supplied page size.
+pub fn limit(requested: Option<i64>) -> i64 {
+    requested.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT)
+}
+
+pub struct Cursor {
+    pub value: Bson,
+    pub id: ObjectId,
+}
+
+/// Encodes a cursor. `sort` identifies the ordering, so a cursor can't be
+/// replayed against a different sort.
+pub fn encode(sort: &str, value: Bson, id: ObjectId) -> String {
+    let raw = doc! { "s": sort, "v": value, "id": id };
+    let bytes = mongodb::bson::to_vec(&raw).unwrap_or_default();
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Decodes a cursor produced by `encode` for the same `sort`.
+pub fn decode(sort: &str, cursor: &str) -> Option<Cursor> {
+    if !cursor.len().is_multiple_of(2) || !cursor.is_ascii() {
+        return None;
+    }
+    let bytes: Vec<u8> = (0..cursor.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&cursor[i..i + 2], 16))
+        .collect::<Result<_, _>>()
+        .ok()?;
+    let raw = Document::from_reader(bytes.as_slice()).ok()?;
+    if raw.get_str("s").ok()? != sort {
+        return None;
+    }
+    Some(Cursor {
+        value: raw.get("v").cloned().unwrap_or(Bson::Null),
+        id: raw.get_object_id("id").ok()?,
+    })
+}
+
+/// Filter matching everything after `cursor` when sorting by `{ field: direction, _id: direction }`.
+///
+/// MongoDB sorts null/missing values lowest, but comparison operators never
+/// match them, so nulls are handled explicitly.
+pub fn after_filter(field: &str, direction: i32, cursor: &Cursor) -> Document {
+    let op = if direction < 0 { "$lt" } else { "$gt" };
+    if field == "_id" {
+        return doc! { "_id": { op: cursor.id } };
+    }
+
+    let mut branches: Vec<Document> = Vec::new();
+    match (&cursor.value, direction < 0) {
+        // nothing sorts below null
+        (Bson::Null, true) => {}
+        (Bson::Null, false) => branches.push(doc! { field: { "$ne": Bson::Null } }),
+        (v, true) => {
+            branches.push(doc! { field: { "$lt": v.clone() } });
+            branches.push(doc! { field: Bson::Null });
+        }
+        (v, false) => branches.push(doc! { field: { "$gt": v.clone() } }),
+    }
+    branches.push(doc! { field: cursor.value.clone(), "_id": { op: cursor.id } });
+
+    doc! { "$or": branches }
+}
@@ -0,0 +1,243 @@
+//! Lexicographic ranks for manual ordering of issues within an organization.
+//!
+//! - A rank is a string of base-36 digits (`0-9a-z`); issues sort by plain string comparison
+//! - A new rank can always be made between two others by adding digits, so a move
+//!   only rewrites the moved issue
+//! - Ranks never end in `0`, which keeps room below every rank
+//! - When ranks get too long (or legacy issues have none), the organization is
+//!   rebalanced to short, evenly spaced ranks
+
+use futures_util::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId, Document};
+
+use crate::server::AppState;
+
+const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+const BASE: u32 = 36;
+/// Ranks longer than this trigger a rebalance.
+pub const MAX_LEN: usize = 32;
+
+fn digit(rank: &[u8], i: usize) -> u32 {
+    rank.get(i)
+        .and_then(|c| DIGITS.iter().position(|d| d == c))
+        .map(|p| p as u32)
+        .unwrap_or(0)
+}
+
+/// A rank sorting strictly after `lower` and before `upper` (`None` = no upper bound).
+/// Returns `None` if `lower >= upper` or a rank is malformed.
+pub fn between(lower: &str, upper: Option<&str>) -> Option<String> {
+    if !valid(lower) || upper.is_some_and(|u| !valid(u) || u.is_empty() || u <= lower) {
+        return None;
+    }
+    let a = lower.as_bytes();
+    let b = upper.map(str::as_bytes);
+
+    let mut out: Vec<u8> = Vec::new();
+    let mut bounded = b.is_some();
+    for i in 0.. {
+        let da = digit(a, i);
+        if !bounded {
+            // anything above `lower`: appends step by one so they stay short,
+            // inserts take the midpoint so repeated inserts at one spot grow slowly
+            let next = if b.is_none() { da + 1 } else { (da + BASE) / 2 };
+            if next > da && next < BASE {
+                out.push(DIGITS[next as usize]);
+                break;
+            }
+            out.push(DIGITS[da as usize]);
+            continue;
+        }
+        let db = digit(b.unwrap_or_default(), i);
+        if db > da + 1 {
+            out.push(DIGITS[((da + db) / 2) as usize]);
+            break;
+        }
+        out.push(DIGITS[da as usize]);
+        // from here on only `lower` constrains the result
+        if db == da + 1 {
+            bounded = false;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+fn valid(rank: &str) -> bool {
+    rank.bytes().all(|c| DIGITS.contains(&c)) && !rank.ends_with('0')
+}
+
+/// `n` short, evenly spaced ranks in ascending order.
+pub fn spread(n: usize) -> Vec<String> {
+    // wide enough to leave ~36 free slots between neighbours
+    let mut width = 1u32;
+    while (BASE as u128).pow(width) < (n as u128 + 1) * BASE as u128 {
+        width += 1;
+    }
+    let step = (BASE as u128).pow(width) / (n as u128 + 1);
+
+    (1..=n as u128)
+        .map(|k| {
+            let mut value = k * step;
+            let mut digits = vec![b'0'; width as usize];
+            for d in digits.iter_mut().rev() {
+                *d = DIGITS[(value % BASE as u128) as usize];
+                value /= BASE as u128;
+            }
+            let rank = String::from_utf8(digits).unwrap_or_default();
+            rank.trim_end_matches('0').to_string()
+        })
+        .collect()
+}
+
+/// Rank for a new issue at the end of an organization.
+pub async fn last(data: &AppState, organization_id: ObjectId) -> mongodb::error::Result<String> {
+    let max = data
+        .issues
+        .clone_with_type::<Document>()
+        .find_one(doc! { "organizationId": organization_id })
+        .sort(doc! { "rank": -1 })
+        .projection(doc! { "rank": 1 })
+        .await?;
+    let max = max.as_ref().and_then(|d| d.get_str("rank").ok()).unwrap_or_default();
+    Ok(between(max, None).unwrap_or_default())
+}
+
+/// Reassigns evenly spaced ranks to every issue of an organization, keeping the
+/// current order (unranked issues first, oldest first).
+pub async fn rebalance(data: &AppState, organization_id: ObjectId) -> mongodb::error::Result<()> {
+    let mut cursor = data
+        .issues
+        .clone_with_type::<Document>()
+        .find(doc! { "organizationId": organization_id })
+        .sort(doc! { "rank": 1, "_id": 1 })
+        .projection(doc! { "_id": 1 })
+        .await?;
+    let mut ids: Vec<ObjectId> = Vec::new();
+    while let Some(d) = cursor.try_next().await? {
+        if let Ok(id) = d.get_object_id("_id") {
+            ids.push(id);
+        }
+    }
+
+    for (id, rank) in ids.iter().zip(spread(ids.len())) {
+        data.issues
+            .update_one(doc! { "_id": id }, doc! { "$set": { "rank": rank } })
+            .await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_between(lower: &str, upper: Option<&str>) -> String {
+        let rank = between(lower, upper).unwrap_or_else(|| panic!("no rank between {lower:?} and {upper:?}"));
+        assert!(valid(&rank), "{rank:?} is not a valid rank");
+        assert!(!rank.ends_with('0'), "{rank:?} ends in 0");
+        assert!(lower < rank.as_str(), "{lower:?} < {rank:?}");
+        if let Some(upper) = upper {
+            assert!(rank.as_str() < upper, "{rank:?} < {upper:?}");
+        }
+        rank
+    }
+
+    #[test]
+    fn empty_bounds() {
+        assert_eq!(assert_between("", None), "1");
+        assert_eq!(assert_between("", Some("1")), "0i");
+        assert_eq!(between("", Some("")), None);
+    }
+
+    #[test]
+    fn appends_step_by_one() {
+        assert_eq!(assert_between("1", None), "2");
+        assert_eq!(assert_between("a", None), "b");
+        assert_eq!(assert_between("ai", None), "b");
+    }
+
+    #[test]
+    fn adjacent_digits() {
+        assert_eq!(assert_between("a", Some("b")), "ai");
+        assert_eq!(assert_between("a", Some("c")), "b");
+        assert_eq!(assert_between("a", Some("a1")), "a0i");
+        assert_eq!(assert_between("az", Some("b")), "azi");
+    }
+
+    #[test]
+    fn upper_edge() {
+        assert_eq!(assert_between("z", None), "z1");
+        assert_eq!(assert_between("zz", None), "zz1");
+        assert_eq!(assert_between("y", Some("z")), "yi");
+        assert_eq!(assert_between("yz", Some("z")), "yzi");
+        assert_between("zy", Some("zz"));
+    }
+
+    #[test]
+    fn rejects_inverted_equal_or_malformed_bounds() {
+        assert_eq!(between("b", Some("a")), None);
+        assert_eq!(between("a", Some("a")), None);
+        assert_eq!(between("a0", None), None);
+        assert_eq!(between("a", Some("b0")), None);
+        assert_eq!(between("A", None), None);
+        assert_eq!(between("a", Some("b-")), None);
+    }
+
+    #[test]
+    fn strictly_ordered_under_repeated_inserts() {
+        // always inserting right below the previous result: the worst case for growth
+        let mut upper = "1".to_string();
+        for _ in 0..100 {
+            upper = assert_between("", Some(&upper));
+        }
+        // always inserting right above the previous result
+        let mut lower = "y".to_string();
+        for _ in 0..100 {
+            lower = assert_between(&lower, Some("z"));
+        }
+        // random-ish positions in a growing list
+        let mut ranks = vec!["1".to_string()];
+        for i in 0..300usize {
+            let at = (i * 7919) % (ranks.len() + 1);
+            let lower = if at == 0 { "" } else { ranks[at - 1].as_str() };
+            let rank = assert_between(lower, ranks.get(at).map(String::as_str));
+            ranks.insert(at, rank);
+        }
+        assert!(ranks.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn max_len_cap() {
+        // inserting at the same spot grows ranks slowly, but eventually past `MAX_LEN`,
+        // which is what makes callers rebalance
+        let mut upper = "1".to_string();
+        let mut inserts = 0;
+        while upper.len() <= MAX_LEN {
+            upper = assert_between("", Some(&upper));
+            inserts += 1;
+        }
+        assert!(inserts > 100, "only {inserts} inserts before hitting MAX_LEN");
+        assert_eq!(upper.len(), MAX_LEN + 1);
+
+        // a rebalance then starts over from short ranks
+        assert!(spread(100_000).iter().all(|r| r.len() < 8));
+    }
+
+    #[test]
+    fn spread_is_ordered_and_short() {
+        assert_eq!(spread(0), Vec::<String>::new());
+        assert_eq!(spread(1), ["i"]);
+        for n in [1, 2, 35, 36, 100, 1000, 5000] {
+            let ranks = spread(n);
+            assert_eq!(ranks.len(), n);
+            assert!(ranks.iter().all(|r| valid(r) && !r.is_empty()), "{n}");
+            assert!(ranks.windows(2).all(|w| w[0] < w[1]), "{n}");
+            // leaves room around every rank, and appending after the last one works
+            assert_between("", Some(&ranks[0]));
+            for w in ranks.windows(2) {
+                assert_between(&w[0], Some(&w[1]));
+            }
+            assert_between(ranks.last().unwrap(), None);
+        }
+    }
+}
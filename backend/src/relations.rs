@@ -0,0 +1,128 @@
+//! Typed, bidirectional relations between issues of one organization.
+//!
+//! - Stored once per pair in a canonical direction: `blocks`, `duplicates` or `relates_to`
+//! - Each side sees the relation in its own terms: "A blocks B" is "B blocked_by A"
+//! - `relates_to` is symmetric; its source is always the smaller id, so a pair is stored only once
+
+use std::collections::HashMap;
+
+use futures_util::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId};
+
+use crate::models::{IssueDb, IssueRelationDb, IssueRelationOut, WorkflowStatus};
+use crate::server::AppState;
+use crate::workflow;
+
+/// Relation types accepted from clients.
+pub const TYPES: [&str; 5] = ["blocks", "blocked_by", "duplicates", "duplicated_by", "relates_to"];
+
+/// Maps a client-facing type to `(stored kind, whether source/target are swapped)`.
+pub fn canonical(kind: &str) -> Option<(&'static str, bool)> {
+    match kind {
+        "blocks" => Some(("blocks", false)),
+        "blocked_by" => Some(("blocks", true)),
+        "duplicates" => Some(("duplicates", false)),
+        "duplicated_by" => Some(("duplicates", true)),
+        "relates_to" => Some(("relates_to", false)),
+        _ => None,
+    }
+}
+
+/// How a stored relation reads from its source (`false`) or target (`true`) side.
+fn seen_from(kind: &str, target_side: bool) -> &'static str {
+    match (kind, target_side) {
+        ("blocks", false) => "blocks",
+        ("blocks", true) => "blocked_by",
+        ("duplicates", false) => "duplicates",
+        ("duplicates", true) => "duplicated_by",
+        _ => "relates_to",
+    }
+}
+
+/// Converts a stored relation to how `issue_id` sees it; `other` is the issue on the far side.
+pub fn to_out(relation: &IssueRelationDb, issue_id: ObjectId, other: &IssueDb) -> IssueRelationOut {
+    let target_side = relation.target_id == issue_id;
+    IssueRelationOut {
+        id: relation.id.to_hex(),
+        kind: seen_from(&relation.kind, target_side),
+        issue_id: other.id.to_hex(),
+        key: other.key.clone(),
+        title: other.title.clone(),
+        status: other.status.clone(),
+    }
+}
+
+/// All relations of an issue, from its point of view.
+pub async fn for_issue(data: &AppState, issue_id: ObjectId) -> mongodb::error::Result<Vec<IssueRelationOut>> {
+    let mut cursor = data
+        .issue_relations
+        .find(doc! { "$or": [{ "sourceId": issue_id }, { "targetId": issue_id }] })
+        .sort(doc! { "_id": 1 })
+        .await?;
+    let mut relations: Vec<IssueRelationDb> = Vec::new();
+    while let Some(r) = cursor.try_next().await? {
+        relations.push(r);
+    }
+
+    let other_ids: Vec<ObjectId> = relations
+        .iter()
+        .map(|r| if r.source_id == issue_id { r.target_id } else { r.source_id })
+        .collect();
+    let mut cursor = data.issues.find(doc! { "_id": { "$in": &other_ids } }).await?;
+    let mut others: HashMap<ObjectId, IssueDb> = HashMap::new();
+    while let Some(i) = cursor.try_next().await? {
+        others.insert(i.id, i);
+    }
+
+    Ok(relations
+        .iter()
+        .filter_map(|r| {
+            let other_id = if r.source_id == issue_id { r.target_id } else { r.source_id };
+            others.get(&other_id).map(|other| to_out(r, issue_id, other))
+        })
+        .collect())
+}
+
+/// Issues that block `issue` and are not closed yet.
+pub async fn open_blockers(
+    data: &AppState,
+    issue: &IssueDb,
+    org_workflow: &[WorkflowStatus],
+) -> mongodb::error::Result<Vec<IssueDb>> {
+    let mut cursor = data
+        .issue_relations
+        .find(doc! { "kind": "blocks", "targetId": issue.id })
+        .await?;
+    let mut blocker_ids: Vec<ObjectId> = Vec::new();
+    while let Some(r) = cursor.try_next().await? {
+        blocker_ids.push(r.source_id);
+    }
+    if blocker_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut cursor = data
+        .issues
+        .find(doc! {
+            "_id": { "$in": blocker_ids },
+            "status": { "$nin": workflow::closed_keys(org_workflow) },
+        })
+        .sort(doc! { "number": 1 })
+        .await?;
+    let mut out = Vec::new();
+    while let Some(i) = cursor.try_next().await? {
+        out.push(i);
+    }
+    Ok(out)
+}
+
+/// Removes every relation touching the given issues.
+pub async fn remove_for(data: &AppState, issue_ids: &[ObjectId]) -> mongodb::error::Result<()> {
+    data.issue_relations
+        .delete_many(doc! { "$or": [
+            { "sourceId": { "$in": issue_ids } },
+            { "targetId": { "$in": issue_ids } },
+        ] })
+        .await?;
+    Ok(())
+}
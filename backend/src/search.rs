@@ -0,0 +1,357 @@
+//! Matching, scoring and highlighting for issue search.
+//!
+//! - MongoDB's `$text` index does the heavy lifting, but only matches whole (stemmed) words
+//! - When it finds nothing, candidates whose words start with a term's first letters are
+//!   loaded and scored here: exact word > word prefix ("auth" -> "authentication") > typo
+//! - Highlights wrap matching words in `<mark>`; the rest of the text is HTML-escaped
+
+use mongodb::bson::{doc, Document};
+
+use crate::models::IssueDb;
+
+/// Max number of terms taken from a query.
+const MAX_TERMS: usize = 8;
+/// Length of a description snippet, in characters.
+const SNIPPET_LEN: usize = 160;
+/// Characters of context kept before the first match in a snippet.
+const SNIPPET_LEAD: usize = 40;
+/// Leading characters of a term that must match exactly for fallback candidates.
+const CANDIDATE_PREFIX: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Match {
+    Fuzzy,
+    Prefix,
+    Exact,
+}
+
+impl Match {
+    fn weight(self) -> f64 {
+        match self {
+            Match::Exact => 1.0,
+            Match::Prefix => 0.75,
+            Match::Fuzzy => 0.5,
+        }
+    }
+}
+
+/// Lowercased, de-duplicated words of a query.
+pub fn terms(q: &str) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for word in q.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()) {
+        let word = word.to_lowercase();
+        if !out.contains(&word) {
+            out.push(word);
+        }
+        if out.len() == MAX_TERMS {
+            break;
+        }
+    }
+    out
+}
+
+fn escape_regex(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if "\\^$.|?*+()[]{}".contains(c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Filter for fallback candidates: title or description has a word starting
+/// with the first few characters of any term (typos after those are scored later).
+pub fn candidate_filter(terms: &[String]) -> Document {
+    let mut branches: Vec<Document> = Vec::new();
+    for term in terms {
+        let prefix: String = term.chars().take(CANDIDATE_PREFIX).collect();
+        let pattern = format!("\\b{}", escape_regex(&prefix));
+        branches.push(doc! { "title": { "$regex": &pattern, "$options": "i" } });
+        branches.push(doc! { "description": { "$regex": &pattern, "$options": "i" } });
+    }
+    doc! { "$or": branches }
+}
+
+/// Levenshtein distance, giving up (returning `max + 1`) once it exceeds `max`.
+fn edit_distance(a: &[char], b: &[char], max: usize) -> usize {
+    if a.len().abs_diff(b.len()) > max {
+        return max + 1;
+    }
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        if cur.iter().min().is_some_and(|m| *m > max) {
+            return max + 1;
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+/// How `word` (lowercase) matches `term`, if at all.
+fn match_word(word: &str, term: &str) -> Option<Match> {
+    if word == term {
+        return Some(Match::Exact);
+    }
+    if word.starts_with(term) {
+        return Some(Match::Prefix);
+    }
+
+    let term: Vec<char> = term.chars().collect();
+    // short terms produce too many false positives
+    let max = match term.len() {
+        0..=3 => return None,
+        4..=6 => 1,
+        _ => 2,
+    };
+    let word: Vec<char> = word.chars().collect();
+    if edit_distance(&term, &word, max) <= max {
+        return Some(Match::Fuzzy);
+    }
+    // a typo inside a partial word, e.g. "authenitc" for "authentication"
+    if word.len() > term.len() && edit_distance(&term, &word[..term.len()], max) <= max {
+        return Some(Match::Fuzzy);
+    }
+    None
+}
+
+/// Character ranges of the words in `text`.
+fn words(text: &[char]) -> Vec<(usize, usize)> {
+    let mut out = Vec::new();
+    let mut start = None;
+    for (i, c) in text.iter().enumerate() {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                out.push((s, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        out.push((s, text.len()));
+    }
+    out
+}
+
+fn best_match(text: &str, term: &str) -> Option<Match> {
+    let chars: Vec<char> = text.chars().collect();
+    words(&chars)
+        .into_iter()
+        .filter_map(|(s, e)| match_word(&chars[s..e].iter().collect::<String>().to_lowercase(), term))
+        .max()
+}
+
+/// Fallback relevance of an issue: for every term, its best match in the
+/// title (counted double) or description. Returns `None` when nothing matches,
+/// otherwise the score and whether any term only matched with a typo.
+pub fn fallback_score(issue: &IssueDb, terms: &[String]) -> Option<(f64, bool)> {
+    let mut score = 0.0;
+    let mut fuzzy = false;
+    for term in terms {
+        let title = best_match(&issue.title, term).map(|m| (m.weight() * 2.0, m));
+        let description = best_match(&issue.description, term).map(|m| (m.weight(), m));
+        let Some((weight, kind)) = [title, description].into_iter().flatten().max_by(|a, b| a.0.total_cmp(&b.0))
+        else {
+            continue;
+        };
+        fuzzy |= kind == Match::Fuzzy;
+        score += weight;
+    }
+    if score > 0.0 { Some((score, fuzzy)) } else { None }
+}
+
+fn push_escaped(out: &mut String, text: &[char]) {
+    for c in text {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(*c),
+        }
+    }
+}
+
+/// HTML-escaped `text` with matching words wrapped in `<mark>`, or `None` if
+/// nothing matches. With `snippet`, long text is cut to a window around the first match.
+pub fn highlight(text: &str, terms: &[String], snippet: bool) -> Option<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let hits: Vec<(usize, usize)> = words(&chars)
+        .into_iter()
+        .filter(|(s, e)| {
+            let word = chars[*s..*e].iter().collect::<String>().to_lowercase();
+            terms.iter().any(|t| match_word(&word, t).is_some())
+        })
+        .collect();
+    let first = hits.first()?.0;
+
+    let (from, to) = if snippet && chars.len() > SNIPPET_LEN {
+        let from = first.saturating_sub(SNIPPET_LEAD).min(chars.len() - SNIPPET_LEN);
+        (from, from + SNIPPET_LEN)
+    } else {
+        (0, chars.len())
+    };
+
+    let mut out = String::new();
+    if from > 0 {
+        out.push('…');
+    }
+    let mut pos = from;
+    for (s, e) in hits {
+        // never split a highlighted word at the snippet edges
+        if s < from || e > to {
+            continue;
+        }
+        push_escaped(&mut out, &chars[pos..s]);
+        out.push_str("<mark>");
+        push_escaped(&mut out, &chars[s..e]);
+        out.push_str("</mark>");
+        pos = e;
+    }
+    push_escaped(&mut out, &chars[pos..to]);
+    if to < chars.len() {
+        out.push('…');
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    fn terms_of(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn query_terms() {
+        assert_eq!(terms("Login, login  BUG!"), ["login", "bug"]);
+        assert!(terms(" -- !? ").is_empty());
+        assert_eq!(terms("a b c d e f g h i j").len(), MAX_TERMS);
+    }
+
+    #[test]
+    fn edit_distance_counts_edits() {
+        assert_eq!(edit_distance(&chars("kitten"), &chars("sitting"), 3), 3);
+        assert_eq!(edit_distance(&chars("login"), &chars("login"), 1), 0);
+        assert_eq!(edit_distance(&chars("logn"), &chars("login"), 1), 1);
+        assert_eq!(edit_distance(&chars(""), &chars("ab"), 2), 2);
+    }
+
+    #[test]
+    fn edit_distance_gives_up_past_max() {
+        // lengths alone rule it out
+        assert_eq!(edit_distance(&chars("ab"), &chars("abcdef"), 1), 2);
+        // every row already exceeds `max`
+        assert_eq!(edit_distance(&chars("abcdef"), &chars("uvwxyz"), 1), 2);
+        assert_eq!(edit_distance(&chars("kitten"), &chars("sitting"), 2), 3);
+    }
+
+    #[test]
+    fn exact_and_prefix_matches() {
+        assert_eq!(match_word("login", "login"), Some(Match::Exact));
+        assert_eq!(match_word("authentication", "auth"), Some(Match::Prefix));
+        // prefixes count even for terms too short for typos
+        assert_eq!(match_word("bugs", "bug"), Some(Match::Prefix));
+        assert_eq!(match_word("auth", "authentication"), None);
+    }
+
+    #[test]
+    fn typo_threshold() {
+        // up to 3 characters: no typos
+        assert_eq!(match_word("bag", "bug"), None);
+        // 4-6 characters: one typo
+        assert_eq!(match_word("logn", "login"), Some(Match::Fuzzy));
+        assert_eq!(match_word("lgn", "login"), None);
+        assert_eq!(match_word("lgoin", "login"), None);
+        // 7 and more: two typos
+        assert_eq!(match_word("authentcaton", "authentication"), Some(Match::Fuzzy));
+        assert_eq!(match_word("athentcaton", "authentication"), None);
+        // a typo inside a partial word
+        assert_eq!(match_word("authentication", "authenitc"), Some(Match::Fuzzy));
+    }
+
+    #[test]
+    fn highlight_escapes_html() {
+        let out = highlight(r#"<b>bug</b> & "x" 'y'"#, &terms_of(&["bug"]), false);
+        assert_eq!(
+            out.as_deref(),
+            Some("&lt;b&gt;<mark>bug</mark>&lt;/b&gt; &amp; &quot;x&quot; &#39;y&#39;")
+        );
+
+        let mut out = String::new();
+        push_escaped(&mut out, &chars("a<b>&\"'"));
+        assert_eq!(out, "a&lt;b&gt;&amp;&quot;&#39;");
+    }
+
+    #[test]
+    fn highlight_marks_every_matching_word() {
+        let out = highlight("Fix login: Login fails", &terms_of(&["login", "fail"]), false);
+        assert_eq!(
+            out.as_deref(),
+            Some("Fix <mark>login</mark>: <mark>Login</mark> <mark>fails</mark>")
+        );
+        assert_eq!(highlight("Nothing here", &terms_of(&["login"]), false), None);
+    }
+
+    #[test]
+    fn snippet_window() {
+        let before = "a ".repeat(100);
+        let after = " b".repeat(100);
+        let text = format!("{before}needle{after}");
+        let terms = terms_of(&["needle"]);
+
+        // without `snippet` the whole text is kept
+        let full = highlight(&text, &terms, false).unwrap();
+        assert!(!full.contains('…'));
+        assert_eq!(full.chars().count(), text.chars().count() + "<mark></mark>".len());
+
+        // cut to `SNIPPET_LEN` characters, starting `SNIPPET_LEAD` before the match
+        let out = highlight(&text, &terms, true).unwrap();
+        let expected = format!(
+            "…{}<mark>needle</mark>{}…",
+            &before[before.len() - SNIPPET_LEAD..],
+            &after[..SNIPPET_LEN - SNIPPET_LEAD - "needle".len()],
+        );
+        assert_eq!(out, expected);
+
+        // a match near the start keeps the beginning, one near the end keeps the end
+        let out = highlight(&format!("needle{after}{after}"), &terms, true).unwrap();
+        assert!(out.starts_with("<mark>needle</mark>") && out.ends_with('…'));
+        let out = highlight(&format!("{before}{before}needle"), &terms, true).unwrap();
+        assert!(out.starts_with('…') && out.ends_with("<mark>needle</mark>"));
+
+        // short text is never cut
+        assert_eq!(highlight("a needle", &terms, true).as_deref(), Some("a <mark>needle</mark>"));
+    }
+
+    #[test]
+    fn snippet_never_splits_a_highlighted_word() {
+        // the second "needle" straddles the end of the window
+        let text = format!("needle{}needle", " ".repeat(SNIPPET_LEN - 9));
+        let out = highlight(&text, &terms_of(&["needle"]), true).unwrap();
+        assert_eq!(out.matches("<mark>").count(), 1);
+        assert!(out.ends_with("nee…"));
+    }
+
+    #[test]
+    fn stemmed_text_hits_have_no_highlight() {
+        // `$text` matches "running" to "runs" by stemming; highlighting doesn't stem,
+        // so such hits come back without a highlight
+        assert_eq!(highlight("Runs every night", &terms_of(&["running"]), false), None);
+        assert_eq!(highlight("Runs every night", &terms_of(&["running"]), true), None);
+    }
+}
@@ -9,7 +9,7 @@ use actix_web::{middleware, App, Error as ActixError, HttpServer};
 use actix_web::http::header;
 use mongodb::{
     bson::doc,
-    options::ClientOptions,
+    options::{ClientOptions, IndexOptions},
     Client,
     Collection,
 };
@@ -20,15 +20,29 @@ use std::time::Duration;
 use tracing::info;
 
 use crate::api;
-use crate::models::{IssueDb, OrganizationDb, UserDb};
+use crate::mailer::{self, Mailer};
+use crate::models::{CommentDb, IssueDb, IssueEventDb, IssueRelationDb, LabelDb, OrganizationDb, PasswordResetDb, SessionDb, StatusTransitionDb, UserDb, ViewDb};
 
 #[derive(Clone)]
 pub struct AppState {
     pub counter: Arc<StdMutex<i32>>,
     pub users: Collection<UserDb>,
+    pub sessions: Collection<SessionDb>,
+    pub password_resets: Collection<PasswordResetDb>,
     pub organizations: Collection<OrganizationDb>,
     pub issues: Collection<IssueDb>,
+    pub labels: Collection<LabelDb>,
+    pub comments: Collection<CommentDb>,
+    pub issue_events: Collection<IssueEventDb>,
+    pub status_transitions: Collection<StatusTransitionDb>,
+    pub issue_relations: Collection<IssueRelationDb>,
+    pub views: Collection<ViewDb>,
     pub jwt_secret: String,
+    pub mailer: Arc<dyn Mailer>,
+    /// Base URL of the web app, for links in emails.
+    pub app_url: String,
+    /// Maximum nesting depth of sub-issues (top-level issues are depth 1).
+    pub max_issue_depth: usize,
 }
 
 /// Simple middleware used for the health endpoints:
@@ -111,6 +125,15 @@ pub async fn api_server(port: u16) -> std::io::Result<()> {
     let web_origin = env_or("WEB_ORIGIN", "http://localhost:3000");
     // JWT secret for signing auth tokens (set a real one in production).
     let jwt_secret = env_or("JWT_SECRET", "dev-secret-change-me");
+    // Links in emails point here (defaults to the configured web origin).
+    let app_url = std::env::var("APP_URL").unwrap_or_else(|_| match web_origin.trim() {
+        "*" => "http://localhost:3000".to_string(),
+        origin => origin.to_string(),
+    });
+    // Outgoing mail: stdout / file / smtp (see `mailer.rs`).
+    let mailer = mailer::from_env().map_err(std::io::Error::other)?;
+    // How deep sub-issues may be nested.
+    let max_issue_depth: usize = env_or("ISSUE_MAX_DEPTH", "5").parse().unwrap_or(5).max(1);
 
     let client_options = ClientOptions::parse(&mongo_uri)
         .await
@@ -118,7 +141,17 @@ pub async fn api_server(port: u16) -> std::io::Result<()> {
     let client = Client::with_options(client_options).map_err(std::io::Error::other)?;
     let db = client.database(&mongo_db);
 
-    // Ensure basic indexes exist (best effort). The text index is created in `seed`.
+    // Ensure basic indexes exist (best effort).
+    // Same name and keys as in `seed`, so running both is harmless.
+    let _ = db
+        .collection::<IssueDb>("issues")
+        .create_index(
+            mongodb::IndexModel::builder()
+                .keys(doc! { "title": "text", "description": "text" })
+                .options(IndexOptions::builder().name("issues_text_search".to_string()).build())
+                .build(),
+        )
+        .await;
     let _ = db
         .collection::<IssueDb>("issues")
         .create_index(mongodb::IndexModel::builder().keys(doc! { "organizationId": 1 }).build())
@@ -127,14 +160,153 @@ pub async fn api_server(port: u16) -> std::io::Result<()> {
         .collection::<IssueDb>("issues")
         .create_index(mongodb::IndexModel::builder().keys(doc! { "parentIssueId": 1 }).build())
         .await;
+    // Issue numbers are unique per organization (legacy issues without a number are skipped).
+    let _ = db
+        .collection::<IssueDb>("issues")
+        .create_index(
+            mongodb::IndexModel::builder()
+                .keys(doc! { "organizationId": 1, "number": 1 })
+                .options(
+                    IndexOptions::builder()
+                        .unique(true)
+                        .partial_filter_expression(doc! { "number": { "$gt": 0 } })
+                        .build(),
+                )
+                .build(),
+        )
+        .await;
+
+    let _ = db
+        .collection::<IssueDb>("issues")
+        .create_index(mongodb::IndexModel::builder().keys(doc! { "labelIds": 1 }).build())
+        .await;
+    let _ = db
+        .collection::<IssueDb>("issues")
+        .create_index(mongodb::IndexModel::builder().keys(doc! { "organizationId": 1, "rank": 1 }).build())
+        .await;
+    let _ = db
+        .collection::<LabelDb>("labels")
+        .create_index(mongodb::IndexModel::builder().keys(doc! { "organizationId": 1 }).build())
+        .await;
+
+    let _ = db
+        .collection::<CommentDb>("comments")
+        .create_index(mongodb::IndexModel::builder().keys(doc! { "issueId": 1, "_id": 1 }).build())
+        .await;
+    let _ = db
+        .collection::<CommentDb>("comments")
+        .create_index(mongodb::IndexModel::builder().keys(doc! { "organizationId": 1 }).build())
+        .await;
+
+    let _ = db
+        .collection::<IssueEventDb>("issue_events")
+        .create_index(mongodb::IndexModel::builder().keys(doc! { "issueId": 1, "_id": 1 }).build())
+        .await;
+    let _ = db
+        .collection::<IssueEventDb>("issue_events")
+        .create_index(mongodb::IndexModel::builder().keys(doc! { "organizationId": 1, "_id": -1 }).build())
+        .await;
+
+    let _ = db
+        .collection::<StatusTransitionDb>("status_transitions")
+        .create_index(mongodb::IndexModel::builder().keys(doc! { "issueId": 1, "at": 1 }).build())
+        .await;
+    let _ = db
+        .collection::<StatusTransitionDb>("status_transitions")
+        .create_index(mongodb::IndexModel::builder().keys(doc! { "organizationId": 1 }).build())
+        .await;
+
+    let _ = db
+        .collection::<IssueRelationDb>("issue_relations")
+        .create_index(mongodb::IndexModel::builder().keys(doc! { "sourceId": 1, "kind": 1 }).build())
+        .await;
+    let _ = db
+        .collection::<IssueRelationDb>("issue_relations")
+        .create_index(mongodb::IndexModel::builder().keys(doc! { "targetId": 1, "kind": 1 }).build())
+        .await;
+    let _ = db
+        .collection::<IssueRelationDb>("issue_relations")
+        .create_index(mongodb::IndexModel::builder().keys(doc! { "organizationId": 1 }).build())
+        .await;
+
+    let _ = db
+        .collection::<ViewDb>("views")
+        .create_index(mongodb::IndexModel::builder().keys(doc! { "organizationId": 1, "ownerId": 1 }).build())
+        .await;
+
+    // One account per email, ignoring case. Fails while duplicates exist;
+    // `cargo run --bin migrate` lists them.
+    let _ = db
+        .collection::<UserDb>("users")
+        .create_index(
+            mongodb::IndexModel::builder()
+                .keys(doc! { "email": 1 })
+                .options(
+                    IndexOptions::builder()
+                        .name("users_email_unique".to_string())
+                        .unique(true)
+                        .collation(crate::models::email_collation())
+                        .build(),
+                )
+                .build(),
+        )
+        .await;
+
+    let _ = db
+        .collection::<SessionDb>("sessions")
+        .create_index(mongodb::IndexModel::builder().keys(doc! { "refreshHash": 1 }).build())
+        .await;
+    let _ = db
+        .collection::<SessionDb>("sessions")
+        .create_index(mongodb::IndexModel::builder().keys(doc! { "previousRefreshHash": 1 }).build())
+        .await;
+    let _ = db
+        .collection::<SessionDb>("sessions")
+        .create_index(mongodb::IndexModel::builder().keys(doc! { "userId": 1 }).build())
+        .await;
+    // MongoDB drops sessions once `expiresAt` has passed
+    let _ = db
+        .collection::<SessionDb>("sessions")
+        .create_index(
+            mongodb::IndexModel::builder()
+                .keys(doc! { "expiresAt": 1 })
+                .options(IndexOptions::builder().expire_after(Duration::ZERO).build())
+                .build(),
+        )
+        .await;
+
+    let _ = db
+        .collection::<PasswordResetDb>("password_resets")
+        .create_index(mongodb::IndexModel::builder().keys(doc! { "tokenHash": 1 }).build())
+        .await;
+    let _ = db
+        .collection::<PasswordResetDb>("password_resets")
+        .create_index(
+            mongodb::IndexModel::builder()
+                .keys(doc! { "expiresAt": 1 })
+                .options(IndexOptions::builder().expire_after(Duration::ZERO).build())
+                .build(),
+        )
+        .await;
 
     let counter = Arc::new(StdMutex::new(0));
     let state = Data::new(AppState {
         counter: counter.clone(),
         users: db.collection::<UserDb>("users"),
+        sessions: db.collection::<SessionDb>("sessions"),
+        password_resets: db.collection::<PasswordResetDb>("password_resets"),
         organizations: db.collection::<OrganizationDb>("organizations"),
         issues: db.collection::<IssueDb>("issues"),
+        labels: db.collection::<LabelDb>("labels"),
+        comments: db.collection::<CommentDb>("comments"),
+        issue_events: db.collection::<IssueEventDb>("issue_events"),
+        status_transitions: db.collection::<StatusTransitionDb>("status_transitions"),
+        issue_relations: db.collection::<IssueRelationDb>("issue_relations"),
+        views: db.collection::<ViewDb>("views"),
         jwt_secret,
+        mailer,
+        app_url: app_url.trim_end_matches('/').to_string(),
+        max_issue_depth,
     });
 
     info!("Actix API listening on port {}", port);
@@ -171,7 +343,7 @@ pub async fn api_server(port: u16) -> std::io::Result<()> {
                         || origin.starts_with("http://172.30.") && origin.ends_with(":3000")
                         || origin.starts_with("http://172.31.") && origin.ends_with(":3000")
                 })
-                .allowed_methods(vec!["GET", "POST", "PUT", "DELETE", "OPTIONS"])
+                .allowed_methods(vec!["GET", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"])
                 .allowed_headers(vec![header::AUTHORIZATION, header::CONTENT_TYPE])
                 // We use Bearer tokens (Authorization header), not cookies.
         }
@@ -186,26 +358,70 @@ pub async fn api_server(port: u16) -> std::io::Result<()> {
             // Health/diagnostics
             .service(api::health::ping::ping)
             .service(api::health::diagnostics::diagnostics)
-            // Auth (signup/login)
+            // Auth (signup/login/refresh/logout/password reset/email verification)
             .service(api::auth::signup::auth_signup)
             .service(api::auth::login::auth_login)
+            .service(api::auth::refresh::auth_refresh)
+            .service(api::auth::logout::auth_logout)
+            .service(api::auth::forgot::auth_forgot)
+            .service(api::auth::reset::auth_reset)
+            .service(api::auth::verify::auth_verify)
+            .service(api::auth::verify_resend::auth_verify_resend)
             // Current user
             .service(api::me::get::me_get)
             .service(api::me::delete::me_delete)
+            .service(api::me::password_update::me_password_update)
+            // Sessions of the current user (list / sign out one / sign out everywhere)
+            .service(api::me::sessions_list::me_sessions_list)
+            .service(api::me::sessions_delete::me_sessions_delete)
+            .service(api::me::sessions_delete_all::me_sessions_delete_all)
             // Organizations (list/get/create)
             .service(api::organizations::list::organizations_list)
             .service(api::organizations::get_by_id::organizations_get_by_id)
             .service(api::organizations::create::organizations_create)
+            .service(api::organizations::patch::organizations_patch)
             .service(api::organizations::add_member::organizations_add_member)
             .service(api::organizations::members_list::organizations_members_list)
             .service(api::organizations::delete::organizations_delete)
-            // Issues (list/search/get/create/update/delete)
+            .service(api::organizations::workflow_get::organizations_workflow_get)
+            .service(api::organizations::workflow_update::organizations_workflow_update)
+            .service(api::organizations::activity::organizations_activity)
+            .service(api::organizations::stats::organizations_stats)
+            .service(api::organizations::flow::organizations_flow)
+            // Search across every organization of the caller
+            .service(api::search::global::search_global)
+            // Issues (list/search/get/create/update/patch/delete)
             .service(api::issues::list::issues_list)
             .service(api::issues::search::issues_search)
             .service(api::issues::get_by_id::issues_get_by_id)
+            .service(api::issues::get_by_key::issues_get_by_key)
+            .service(api::issues::history::issues_history)
+            .service(api::issues::tree::issues_tree)
             .service(api::issues::create::issues_create)
             .service(api::issues::update::issues_update)
+            .service(api::issues::patch::issues_patch)
+            .service(api::issues::move_issue::issues_move)
             .service(api::issues::delete::issues_delete)
+            // Comments (per issue)
+            .service(api::comments::list::comments_list)
+            .service(api::comments::create::comments_create)
+            .service(api::comments::update::comments_update)
+            .service(api::comments::delete::comments_delete)
+            // Relations between issues (blocks/duplicates/relates_to)
+            .service(api::relations::create::relations_create)
+            .service(api::relations::delete::relations_delete)
+            // Labels (list/create/update/delete)
+            .service(api::labels::list::labels_list)
+            .service(api::labels::create::labels_create)
+            .service(api::labels::update::labels_update)
+            .service(api::labels::delete::labels_delete)
+            // Saved views (named filters; private or shared with the org)
+            .service(api::views::list::views_list)
+            .service(api::views::create::views_create)
+            .service(api::views::issues::views_issues)
+            .service(api::views::get_by_id::views_get_by_id)
+            .service(api::views::update::views_update)
+            .service(api::views::delete::views_delete)
     })
     .workers(8)
     .keep_alive(Duration::from_secs(60))
@@ -0,0 +1,153 @@
+//! Login sessions and their refresh tokens.
+//!
+//! - Login/signup start a session; every access token names it (`sid`)
+//! - Refresh tokens are random and stored only as a SHA-256 hash
+//! - Each refresh rotates the token; presenting an already-rotated token
+//!   deletes the whole session, since it was most likely copied
+//! - Deleting a session (logout) makes its access tokens fail right away
+//! - Sessions remember the client (user agent, IP) and when they were last used
+
+use actix_web::http::header;
+use actix_web::HttpRequest;
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use mongodb::options::ReturnDocument;
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use crate::models::{rfc3339, SessionDb, SessionOut};
+use crate::server::AppState;
+
+/// Sessions unused for this long expire.
+pub const REFRESH_TOKEN_DAYS: i64 = 30;
+/// `lastSeenAt` is only written when it is older than this.
+const LAST_SEEN_RESOLUTION_MS: i64 = 60 * 1000;
+
+/// The client a request comes from, as shown in the session list.
+pub struct Client {
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+}
+
+pub fn client(req: &HttpRequest) -> Client {
+    let user_agent = req
+        .headers()
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.chars().take(512).collect());
+    // honours `X-Forwarded-For` / `Forwarded` when running behind a proxy
+    let ip = req.connection_info().realip_remote_addr().map(str::to_string);
+    Client { user_agent, ip }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// A random 256-bit token (hex); store only its `hash_token`.
+pub fn new_token() -> String {
+    hex(&rand::random::<[u8; 32]>())
+}
+
+pub fn hash_token(token: &str) -> String {
+    hex(&Sha256::digest(token.trim().as_bytes()))
+}
+
+fn expires_at() -> DateTime {
+    DateTime::from_millis(DateTime::now().timestamp_millis() + REFRESH_TOKEN_DAYS * 24 * 60 * 60 * 1000)
+}
+
+/// Starts a session for `user_id`; returns it with its (plain) refresh token.
+pub async fn start(
+    data: &AppState,
+    user_id: ObjectId,
+    client: Client,
+) -> mongodb::error::Result<(SessionDb, String)> {
+    let token = new_token();
+    let now = DateTime::now();
+    let session = SessionDb {
+        id: ObjectId::new(),
+        user_id,
+        refresh_hash: hash_token(&token),
+        previous_refresh_hash: None,
+        created_at: now,
+        expires_at: expires_at(),
+        user_agent: client.user_agent,
+        ip: client.ip,
+        last_seen_at: Some(now),
+    };
+    data.sessions.insert_one(&session).await?;
+    Ok((session, token))
+}
+
+/// Swaps a refresh token for a new one. `None` if the token is unknown,
+/// expired or was already used (which also ends its session).
+pub async fn rotate(
+    data: &AppState,
+    refresh_token: &str,
+    client: Client,
+) -> mongodb::error::Result<Option<(SessionDb, String)>> {
+    let hash = hash_token(refresh_token);
+    let token = new_token();
+    // matching on the current hash makes concurrent refreshes with one token fail all but one
+    let rotated = data
+        .sessions
+        .find_one_and_update(
+            doc! { "refreshHash": &hash, "expiresAt": { "$gt": DateTime::now() } },
+            doc! { "$set": {
+                "refreshHash": hash_token(&token),
+                "previousRefreshHash": &hash,
+                "expiresAt": expires_at(),
+                "userAgent": client.user_agent,
+                "ip": client.ip,
+                "lastSeenAt": DateTime::now(),
+            } },
+        )
+        .return_document(ReturnDocument::After)
+        .await?;
+    match rotated {
+        Some(session) => Ok(Some((session, token))),
+        None => {
+            data.sessions.delete_many(doc! { "previousRefreshHash": &hash }).await?;
+            Ok(None)
+        }
+    }
+}
+
+/// Ends the session a refresh token belongs to (current or just rotated).
+pub async fn end_by_token(data: &AppState, refresh_token: &str) -> mongodb::error::Result<()> {
+    let hash = hash_token(refresh_token);
+    data.sessions
+        .delete_many(doc! { "$or": [{ "refreshHash": &hash }, { "previousRefreshHash": &hash }] })
+        .await?;
+    Ok(())
+}
+
+/// Records that `session` was just used (best effort, throttled).
+pub async fn touch(data: &AppState, session: &SessionDb) {
+    let now = DateTime::now();
+    let stale = session
+        .last_seen_at
+        .is_none_or(|t| now.timestamp_millis() - t.timestamp_millis() >= LAST_SEEN_RESOLUTION_MS);
+    if !stale {
+        return;
+    }
+    if let Err(e) = data
+        .sessions
+        .update_one(doc! { "_id": session.id }, doc! { "$set": { "lastSeenAt": now } })
+        .await
+    {
+        warn!("failed to update last-seen time of session {}: {e}", session.id);
+    }
+}
+
+/// API view of a session; `current_id` is the session making the request.
+pub fn to_out(session: SessionDb, current_id: ObjectId) -> SessionOut {
+    SessionOut {
+        id: session.id.to_hex(),
+        user_agent: session.user_agent,
+        ip: session.ip,
+        created_at: rfc3339(session.created_at),
+        last_seen_at: rfc3339(session.last_seen_at.unwrap_or(session.created_at)),
+        current: session.id == current_id,
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::HashSet;
+
+use crate::models::WorkflowStatus;
+
+/// Per-organization workflow statuses.
+///
+/// - Organizations without a stored workflow use `default_workflow()`
+/// - Issue create/update validate `status` against the organization's workflow
+/// - `transitions` restricts which statuses an issue may move to next
+pub const CATEGORIES: [&str; 4] = ["unstarted", "started", "completed", "cancelled"];
+
+const MAX_STATUSES: usize = 20;
+
+fn status(key: &str, name: &str, category: &str) -> WorkflowStatus {
+    WorkflowStatus {
+        key: key.to_string(),
+        name: name.to_string(),
+        category: category.to_string(),
+        transitions: Vec::new(),
+    }
+}
+
+/// The statuses every organization had before workflows were configurable.
+pub fn default_workflow() -> Vec<WorkflowStatus> {
+    vec![
+        status("todo", "Todo", "unstarted"),
+        status("in_progress", "In Progress", "started"),
+        status("in_review", "In Review", "started"),
+        status("done", "Done", "completed"),
+    ]
+}
+
+/// Returns the stored workflow, or the default one if none was configured.
+pub fn effective(stored: &[WorkflowStatus]) -> Vec<WorkflowStatus> {
+    if stored.is_empty() {
+        default_workflow()
+    } else {
+        stored.to_vec()
+    }
+}
+
+/// Finds the workflow status for a client-supplied status key.
+///
+/// `backlog` is kept as a legacy alias for `in_review` unless the workflow
+/// defines its own `backlog` status.
+pub fn resolve_status<'a>(workflow: &'a [WorkflowStatus], status: &str) -> Option<&'a WorkflowStatus> {
+    let find = |key: &str| workflow.iter().find(|s| s.key == key);
+    match find(status) {
+        Some(s) => Some(s),
+        None if status == "backlog" => find("in_review"),
+        None => None,
+    }
+}
+
+/// Whether an issue may move from `from` to `to`.
+/// Unknown `from` statuses (e.g. removed from the workflow) may move anywhere.
+pub fn can_transition(workflow: &[WorkflowStatus], from: &str, to: &str) -> bool {
+    if from == to {
+        return true;
+    }
+    match workflow.iter().find(|s| s.key == from) {
+        Some(s) => s.transitions.is_empty() || s.transitions.iter().any(|t| t == to),
+        None => true,
+    }
+}
+
+/// Whether `status` is a `completed`-category status of the workflow.
+pub fn is_completed(workflow: &[WorkflowStatus], status: &str) -> bool {
+    workflow.iter().any(|s| s.key == status && s.category == "completed")
+}
+
+/// Status keys of the `completed` and `cancelled` categories; everything else counts as open.
+pub fn closed_keys(workflow: &[WorkflowStatus]) -> Vec<String> {
+    workflow
+        .iter()
+        .filter(|s| s.category == "completed" || s.category == "cancelled")
+        .map(|s| s.key.clone())
+        .collect()
+}
+
+fn valid_key(key: &str) -> bool {
+    !key.is_empty()
+        && key.len() <= 32
+        && key.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Validates a workflow submitted by a client.
+pub fn validate(statuses: &[WorkflowStatus]) -> Result<(), String> {
+    if statuses.is_empty() {
+        return Err("Workflow must have at least one status".to_string());
+    }
+    if statuses.len() > MAX_STATUSES {
+        return Err(format!("Workflow can have at most {MAX_STATUSES} statuses"));
+    }
+
+    let mut keys: HashSet<&str> = HashSet::new();
+    for s in statuses {
+        if !valid_key(&s.key) {
+            return Err(format!(
+                "Invalid status key '{}' (use 1-32 lowercase letters, digits or _)",
+                s.key
+            ));
+        }
+        if s.name.trim().is_empty() {
+            return Err(format!("Status '{}' needs a name", s.key));
+        }
+        if !CATEGORIES.contains(&s.category.as_str()) {
+            return Err(format!(
+                "Invalid category '{}' for status '{}' (expected one of: {})",
+                s.category,
+                s.key,
+                CATEGORIES.join(", ")
+            ));
+        }
+        if !keys.insert(s.key.as_str()) {
+            return Err(format!("Duplicate status key '{}'", s.key));
+        }
+    }
+
+    for s in statuses {
+        if let Some(t) = s.transitions.iter().find(|t| !keys.contains(t.as_str())) {
+            return Err(format!("Status '{}' has a transition to unknown status '{}'", s.key, t));
+        }
+    }
+    Ok(())
+}
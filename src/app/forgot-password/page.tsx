@@ -0,0 +1,68 @@
+'use client'
+
+import Link from 'next/link'
+import { useState } from 'react'
+import AuthShell from '@/components/AuthShell'
+import { apiJson } from '@/lib/apiClient'
+
+export default function ForgotPasswordPage() {
+  const [email, setEmail] = useState('')
+  const [sent, setSent] = useState(false)
+  const [loading, setLoading] = useState(false)
+  const [error, setError] = useState<string | null>(null)
+
+  async function onSubmit() {
+    setLoading(true)
+    setError(null)
+    try {
+      await apiJson('/api/auth/forgot', { method: 'POST', body: JSON.stringify({ email }) })
+      setSent(true)
+    } catch (e) {
+      setError((e as Error).message)
+    } finally {
+      setLoading(false)
+    }
+  }
+
+  return (
+    <AuthShell title="Forgot password" subtitle="We'll email you a link to choose a new password.">
+      {error ? (
+        <div className="mb-4 rounded-2xl border border-red-200 bg-red-50 p-3 text-sm text-red-900 dark:border-red-900/40 dark:bg-red-950/40 dark:text-red-200">
+          {error}
+        </div>
+      ) : null}
+
+      {sent ? (
+        <p className="text-sm text-zinc-600 dark:text-zinc-400">
+          If an account exists for <span className="font-mono">{email}</span>, a reset link is on its way.
+        </p>
+      ) : (
+        <div className="grid gap-3">
+          <input
+            className="h-11 w-full rounded-2xl border border-zinc-200 bg-white px-3 text-sm outline-none dark:border-zinc-800 dark:bg-zinc-950"
+            placeholder="Email"
+            value={email}
+            onChange={(e) => setEmail(e.target.value)}
+            onKeyDown={(e) => {
+              if (e.key === 'Enter') void onSubmit()
+            }}
+          />
+
+          <button
+            className="h-11 rounded-2xl bg-zinc-900 px-4 text-sm font-semibold text-white hover:bg-zinc-800 disabled:opacity-60 dark:bg-zinc-50 dark:text-zinc-950 dark:hover:bg-white"
+            onClick={() => void onSubmit()}
+            disabled={loading}
+          >
+            Send reset link
+          </button>
+        </div>
+      )}
+
+      <p className="mt-6 text-sm text-zinc-600 dark:text-zinc-400">
+        <Link href="/login" className="font-semibold text-zinc-950 dark:text-zinc-50">
+          Back to log in
+        </Link>
+      </p>
+    </AuthShell>
+  )
+}
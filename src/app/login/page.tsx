@@ -72,6 +72,12 @@ export default function LoginPage() {
       </div>
 
       <p className="mt-6 text-sm text-zinc-600 dark:text-zinc-400">
+        <Link href="/forgot-password" className="font-semibold text-zinc-950 dark:text-zinc-50">
+          Forgot password?
+        </Link>
+      </p>
+
+      <p className="mt-2 text-sm text-zinc-600 dark:text-zinc-400">
         No account?{' '}
         <Link href="/signup" className="font-semibold text-zinc-950 dark:text-zinc-50">
           Sign up
@@ -11,12 +11,13 @@ import { clearAuth } from '@/lib/authStorage'
 export default function ProfilePage() {
   const router = useRouter()
   const { isAuthed, logout, user } = useAuth()
-  const { me, loading: meLoading, error: meError, deleteAccount } = useMe()
+  const { me, loading: meLoading, error: meError, deleteAccount, resendVerification } = useMe()
   const { organizations, loading, error, refresh, remove, addMember } = useOrganizations()
 
   const [inviteEmail, setInviteEmail] = useState<Record<string, string>>({})
   const [busyOrgId, setBusyOrgId] = useState<string | null>(null)
   const [actionError, setActionError] = useState<string | null>(null)
+  const [verificationSent, setVerificationSent] = useState(false)
 
   useEffect(() => {
     if (!isAuthed) router.push('/login')
@@ -57,6 +58,16 @@ export default function ProfilePage() {
     }
   }
 
+  async function onResendVerification() {
+    setActionError(null)
+    try {
+      await resendVerification()
+      setVerificationSent(true)
+    } catch (e) {
+      setActionError((e as Error).message)
+    }
+  }
+
   async function onDeleteAccount() {
     if (!confirm('Delete your account? This will delete your owned organizations and issues.')) return
     setActionError(null)
@@ -112,6 +123,18 @@ export default function ProfilePage() {
                   Email
                 </div>
                 <div className="mt-0.5 font-mono text-xs">{effectiveUser.email}</div>
+                {effectiveUser.unverified ? (
+                  <div className="mt-1 text-xs text-amber-700 dark:text-amber-300">
+                    Not verified yet.{' '}
+                    {verificationSent ? (
+                      'Check your inbox.'
+                    ) : (
+                      <button className="font-semibold underline" onClick={() => void onResendVerification()}>
+                        Resend link
+                      </button>
+                    )}
+                  </div>
+                ) : null}
               </div>
               <div className="pt-2">
                 <button
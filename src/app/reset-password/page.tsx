@@ -0,0 +1,75 @@
+'use client'
+
+import Link from 'next/link'
+import { useRouter, useSearchParams } from 'next/navigation'
+import { Suspense, useState } from 'react'
+import AuthShell from '@/components/AuthShell'
+import { apiJson } from '@/lib/apiClient'
+
+function ResetPasswordForm() {
+  const router = useRouter()
+  const token = useSearchParams().get('token') ?? ''
+  const [password, setPassword] = useState('')
+  const [loading, setLoading] = useState(false)
+  const [error, setError] = useState<string | null>(null)
+
+  async function onSubmit() {
+    setLoading(true)
+    setError(null)
+    try {
+      await apiJson('/api/auth/reset', { method: 'POST', body: JSON.stringify({ token, password }) })
+      router.push('/login')
+    } catch (e) {
+      setError((e as Error).message)
+    } finally {
+      setLoading(false)
+    }
+  }
+
+  return (
+    <>
+      {error ? (
+        <div className="mb-4 rounded-2xl border border-red-200 bg-red-50 p-3 text-sm text-red-900 dark:border-red-900/40 dark:bg-red-950/40 dark:text-red-200">
+          {error}
+        </div>
+      ) : null}
+
+      <div className="grid gap-3">
+        <input
+          className="h-11 w-full rounded-2xl border border-zinc-200 bg-white px-3 text-sm outline-none dark:border-zinc-800 dark:bg-zinc-950"
+          placeholder="New password (min. 8 characters)"
+          type="password"
+          value={password}
+          onChange={(e) => setPassword(e.target.value)}
+          onKeyDown={(e) => {
+            if (e.key === 'Enter') void onSubmit()
+          }}
+        />
+
+        <button
+          className="h-11 rounded-2xl bg-zinc-900 px-4 text-sm font-semibold text-white hover:bg-zinc-800 disabled:opacity-60 dark:bg-zinc-50 dark:text-zinc-950 dark:hover:bg-white"
+          onClick={() => void onSubmit()}
+          disabled={loading || !token}
+        >
+          Set new password
+        </button>
+      </div>
+    </>
+  )
+}
+
+export default function ResetPasswordPage() {
+  return (
+    <AuthShell title="Choose a new password" subtitle="You'll be signed out on all devices.">
+      <Suspense>
+        <ResetPasswordForm />
+      </Suspense>
+
+      <p className="mt-6 text-sm text-zinc-600 dark:text-zinc-400">
+        <Link href="/forgot-password" className="font-semibold text-zinc-950 dark:text-zinc-50">
+          Request a new link
+        </Link>
+      </p>
+    </AuthShell>
+  )
+}
@@ -0,0 +1,50 @@
+'use client'
+
+import Link from 'next/link'
+import { useSearchParams } from 'next/navigation'
+import { Suspense, useEffect, useState } from 'react'
+import AuthShell from '@/components/AuthShell'
+import { apiJson } from '@/lib/apiClient'
+
+function VerifyEmailResult() {
+  const token = useSearchParams().get('token') ?? ''
+  const [status, setStatus] = useState<'pending' | 'done' | 'failed'>('pending')
+  const [error, setError] = useState<string | null>(null)
+
+  useEffect(() => {
+    apiJson('/api/auth/verify', { method: 'POST', body: JSON.stringify({ token }) })
+      .then(() => setStatus('done'))
+      .catch((e: Error) => {
+        setError(e.message)
+        setStatus('failed')
+      })
+  }, [token])
+
+  if (status === 'pending') {
+    return <p className="text-sm text-zinc-600 dark:text-zinc-400">Checking your link…</p>
+  }
+  if (status === 'failed') {
+    return (
+      <div className="rounded-2xl border border-red-200 bg-red-50 p-3 text-sm text-red-900 dark:border-red-900/40 dark:bg-red-950/40 dark:text-red-200">
+        {error} You can request a new link from your profile.
+      </div>
+    )
+  }
+  return <p className="text-sm text-zinc-600 dark:text-zinc-400">Your email is confirmed.</p>
+}
+
+export default function VerifyEmailPage() {
+  return (
+    <AuthShell title="Confirm email">
+      <Suspense>
+        <VerifyEmailResult />
+      </Suspense>
+
+      <p className="mt-6 text-sm text-zinc-600 dark:text-zinc-400">
+        <Link href="/" className="font-semibold text-zinc-950 dark:text-zinc-50">
+          Continue to the app
+        </Link>
+      </p>
+    </AuthShell>
+  )
+}
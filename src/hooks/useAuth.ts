@@ -2,7 +2,7 @@
 
 import { useCallback, useMemo, useState } from 'react'
 import { apiJson } from '@/lib/apiClient'
-import { clearAuth, getToken, getUser, setAuth } from '@/lib/authStorage'
+import { clearAuth, getRefreshToken, getToken, getUser, setAuth } from '@/lib/authStorage'
 import type { AuthResponse, User } from '@/lib/types'
 import type { LoginInput, SignupInput } from '@/lib/validation'
 
@@ -17,7 +17,7 @@ export function useAuth() {
       method: 'POST',
       body: JSON.stringify(body),
     })
-    setAuth(res.token, res.user)
+    setAuth(res.token, res.refreshToken, res.user)
     setUser(res.user)
     setToken(res.token)
     return res
@@ -28,13 +28,18 @@ export function useAuth() {
       method: 'POST',
       body: JSON.stringify(body),
     })
-    setAuth(res.token, res.user)
+    setAuth(res.token, res.refreshToken, res.user)
     setUser(res.user)
     setToken(res.token)
     return res
   }, [])
 
   const logout = useCallback(() => {
+    // end the session server-side too (best effort; local state is cleared either way)
+    const refreshToken = getRefreshToken()
+    if (refreshToken) {
+      apiJson('/api/auth/logout', { method: 'POST', body: JSON.stringify({ refreshToken }) }).catch(() => {})
+    }
     clearAuth()
     setUser(null)
     setToken(null)
@@ -1,7 +1,7 @@
 'use client'
 
 import { useCallback, useEffect, useState } from 'react'
-import { apiJson } from '@/lib/apiClient'
+import { apiAllPages, apiJson } from '@/lib/apiClient'
 import type { Issue, Organization } from '@/lib/types'
 
 export function useIssue(issueId: string | null) {
@@ -29,7 +29,7 @@ export function useIssue(issueId: string | null) {
       const org = await apiJson<Organization>(`/api/organizations/${i.organizationId}`)
       setOrganization(org)
 
-      const subs = await apiJson<Issue[]>(
+      const subs = await apiAllPages<Issue>(
         `/api/issues?organizationId=${encodeURIComponent(i.organizationId)}&parentIssueId=${encodeURIComponent(i._id)}`,
       )
       setSubIssues(subs)
@@ -1,8 +1,8 @@
 'use client'
 
 import { useCallback, useEffect, useMemo, useState } from 'react'
-import { apiJson } from '@/lib/apiClient'
-import type { Issue } from '@/lib/types'
+import { apiAllPages } from '@/lib/apiClient'
+import type { Issue, SearchHit } from '@/lib/types'
 
 type State = {
   issues: Issue[]
@@ -28,8 +28,8 @@ export function useIssues(organizationId: string | null) {
     }
     setState((s) => ({ ...s, loading: true, error: null }))
     try {
-      const issues = await apiJson<Issue[]>(
-        `/api/issues?organizationId=${encodeURIComponent(organizationId)}`,
+      const issues = await apiAllPages<Issue>(
+        `/api/issues?organizationId=${encodeURIComponent(organizationId)}&limit=200`,
       )
       setState((s) => ({ ...s, issues, loading: false }))
     } catch (e) {
@@ -44,7 +44,7 @@ export function useIssues(organizationId: string | null) {
 
     setState((s) => ({ ...s, loading: true, error: null }))
     try {
-      const issues = await apiJson<Issue[]>(
+      const issues = await apiAllPages<SearchHit>(
         `/api/issues/search?q=${encodeURIComponent(q)}&organizationId=${encodeURIComponent(organizationId)}`,
       )
       setState((s) => ({ ...s, issues, loading: false }))
@@ -30,6 +30,10 @@ export function useMe() {
     return apiJson<{ ok: boolean }>('/api/me', { method: 'DELETE' })
   }, [])
 
-  return { me, loading, error, refresh, deleteAccount }
+  const resendVerification = useCallback(async () => {
+    return apiJson<{ ok: boolean }>('/api/auth/verify/resend', { method: 'POST' })
+  }, [])
+
+  return { me, loading, error, refresh, deleteAccount, resendVerification }
 }
 
@@ -1,26 +1,64 @@
 'use client'
 
 import { apiBase } from '@/lib/apiBase'
-import { getToken } from '@/lib/authStorage'
+import { clearAuth, getRefreshToken, getToken, setAuth } from '@/lib/authStorage'
+import type { AuthResponse, Page } from '@/lib/types'
+
+let refreshing: Promise<boolean> | null = null
+
+/**
+ * Trades the stored refresh token for a new token pair.
+ * Concurrent callers share one request, since each refresh token works only once.
+ */
+async function refreshSession(): Promise<boolean> {
+  const refreshToken = getRefreshToken()
+  if (!refreshToken) return false
+  refreshing ??= (async () => {
+    try {
+      const res = await fetch(`${apiBase()}/api/auth/refresh`, {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify({ refreshToken }),
+      })
+      if (!res.ok) {
+        clearAuth()
+        return false
+      }
+      const body = (await res.json()) as AuthResponse
+      setAuth(body.token, body.refreshToken, body.user)
+      return true
+    } catch {
+      return false
+    } finally {
+      refreshing = null
+    }
+  })()
+  return refreshing
+}
 
 /**
  * Small fetch wrapper for the React client.
  *
  * - Prepends `NEXT_PUBLIC_API_BASE`
  * - Attaches `Authorization: Bearer <token>` when logged in
+ * - On 401, refreshes the access token once and retries
  * - Normalizes error responses to `Error(message)`
  *
  * This is used by all client-side hooks (useAuth/useIssues/etc).
  */
 export async function apiJson<T>(path: string, init?: RequestInit): Promise<T> {
-  const token = getToken()
-  const headers: Record<string, string> = {
-    ...(init?.headers as Record<string, string> | undefined),
+  const send = () => {
+    const token = getToken()
+    const headers: Record<string, string> = {
+      ...(init?.headers as Record<string, string> | undefined),
+    }
+    if (token) headers.Authorization = `Bearer ${token}`
+    if (init?.body) headers['Content-Type'] = headers['Content-Type'] ?? 'application/json'
+    return fetch(`${apiBase()}${path}`, { ...init, headers })
   }
-  if (token) headers.Authorization = `Bearer ${token}`
-  if (init?.body) headers['Content-Type'] = headers['Content-Type'] ?? 'application/json'
 
-  const res = await fetch(`${apiBase()}${path}`, { ...init, headers })
+  let res = await send()
+  if (res.status === 401 && getToken() && (await refreshSession())) res = await send()
 
   if (!res.ok) {
     const body = (await res.json().catch(() => null)) as { message?: string } | null
@@ -29,3 +67,21 @@ export async function apiJson<T>(path: string, init?: RequestInit): Promise<T> {
   return (await res.json()) as T
 }
 
+
+/**
+ * Fetches every page of a cursor-paginated endpoint (`{ items, nextCursor }`)
+ * and returns the concatenated items.
+ */
+export async function apiAllPages<T>(path: string): Promise<T[]> {
+  const items: T[] = []
+  const sep = path.includes('?') ? '&' : '?'
+  let cursor: string | null = null
+  do {
+    const page: Page<T> = await apiJson<Page<T>>(
+      cursor ? `${path}${sep}after=${encodeURIComponent(cursor)}` : path,
+    )
+    items.push(...page.items)
+    cursor = page.nextCursor
+  } while (cursor)
+  return items
+}
@@ -5,8 +5,10 @@ import type { User } from '@/lib/types'
  *
  * We keep auth client-side (JWT in localStorage) because this is a semester project
  * and we want a simple "login -> call API" flow without cookies/session storage.
+ * The access token is short-lived; `apiClient` renews it with the refresh token.
  */
 const TOKEN_KEY = 'dazabaze.token'
+const REFRESH_KEY = 'dazabaze.refreshToken'
 const USER_KEY = 'dazabaze.user'
 const ORG_KEY = 'dazabaze.orgId'
 
@@ -15,8 +17,14 @@ export function getToken(): string | null {
   return localStorage.getItem(TOKEN_KEY)
 }
 
-export function setAuth(token: string, user: User) {
+export function getRefreshToken(): string | null {
+  if (typeof window === 'undefined') return null
+  return localStorage.getItem(REFRESH_KEY)
+}
+
+export function setAuth(token: string, refreshToken: string, user: User) {
   localStorage.setItem(TOKEN_KEY, token)
+  localStorage.setItem(REFRESH_KEY, refreshToken)
   localStorage.setItem(USER_KEY, JSON.stringify(user))
 }
 
@@ -33,6 +41,7 @@ export function getUser(): User | null {
 
 export function clearAuth() {
   localStorage.removeItem(TOKEN_KEY)
+  localStorage.removeItem(REFRESH_KEY)
   localStorage.removeItem(USER_KEY)
 }
 
@@ -2,28 +2,134 @@ export type User = {
   _id: string
   email: string
   name: string
+  unverified: boolean
 }
 
 export type AuthResponse = {
   token: string
+  expiresIn: number
+  refreshToken: string
   user: User
 }
 
-export type Organization = {
+export type Session = {
+  _id: string
+  userAgent: string | null
+  ip: string | null
+  createdAt: string
+  lastSeenAt: string
+  current: boolean
+}
+
+export type Audit = {
+  createdAt: string
+  createdBy: string | null
+  updatedAt: string
+  updatedBy: string | null
+}
+
+export type Organization = Audit & {
   _id: string
   name: string
   key: string
   ownerId: string
   memberIds: string[]
+  requireVerifiedMembers: boolean
 }
 
-export type Issue = {
+export type Issue = Audit & {
   _id: string
   organizationId: string
+  number: number
+  key: string
   title: string
   description: string
   status: string
   assigneeId?: string | null
   parentIssueId?: string | null
+  priority: 'none' | 'low' | 'medium' | 'high' | 'urgent'
+  estimate?: number | null
+  startDate?: string | null
+  dueDate?: string | null
+  labelIds: string[]
+  completedAt?: string | null
+  rank: string
+}
+
+export type IssueRelation = {
+  _id: string
+  type: 'blocks' | 'blocked_by' | 'duplicates' | 'duplicated_by' | 'relates_to'
+  issueId: string
+  key: string
+  title: string
+  status: string
+}
+
+export type IssueDetail = Issue & {
+  relations: IssueRelation[]
+}
+
+export type OpenTotal = {
+  open: number
+  total: number
+}
+
+export type OrganizationStats = OpenTotal & {
+  byStatus: { status: string; name: string; category: string | null; count: number }[]
+  byAssignee: (OpenTotal & { assigneeId: string; name: string | null })[]
+  unassigned: OpenTotal
+  openSubIssues: (OpenTotal & { parentIssueId: string; key: string | null; title: string | null })[]
+  window: { since: string; until: string; created: number; closed: number }
+}
+
+export type Flow = {
+  statuses: string[]
+  days: { date: string; counts: Record<string, number>; open: number; total: number }[]
 }
 
+export type Label = Audit & {
+  _id: string
+  organizationId: string
+  name: string
+  color: string
+}
+
+
+export type View = Audit & {
+  _id: string
+  organizationId: string
+  ownerId: string
+  name: string
+  filter: string
+  sort: string
+  order: 'asc' | 'desc'
+  shared: boolean
+}
+
+export type Comment = {
+  _id: string
+  issueId: string
+  authorId: string
+  body: string
+  createdAt: string
+  editedAt?: string | null
+  editCount: number
+}
+
+export type SearchHit = Issue & {
+  score: number
+  match: 'text' | 'prefix' | 'fuzzy'
+  highlights: {
+    title: string | null
+    description: string | null
+  }
+}
+
+export type GlobalSearchHit = SearchHit & {
+  organization: Pick<Organization, '_id' | 'name' | 'key'>
+}
+
+export type Page<T> = {
+  items: T[]
+  nextCursor: string | null
+}